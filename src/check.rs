@@ -0,0 +1,397 @@
+/*
+* BSD Zero Clause License
+*
+* Copyright (c) 2025 sockentrocken
+*
+* Permission to use, copy, modify, and/or distribute this software for any
+* purpose with or without fee is hereby granted.
+*
+* THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHOR DISCLAIMS ALL WARRANTIES WITH
+* REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF MERCHANTABILITY
+* AND FITNESS. IN NO EVENT SHALL THE AUTHOR BE LIABLE FOR ANY SPECIAL, DIRECT,
+* INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES WHATSOEVER RESULTING FROM
+* LOSS OF USE, DATA OR PROFITS, WHETHER IN AN ACTION OF CONTRACT, NEGLIGENCE OR
+* OTHER TORTIOUS ACTION, ARISING OUT OF OR IN CONNECTION WITH THE USE OR
+* PERFORMANCE OF THIS SOFTWARE.
+*/
+
+use crate::editor::*;
+use crate::geometry::*;
+use crate::helper::*;
+use crate::selection::*;
+
+//================================================================
+
+use raylib::prelude::*;
+
+//================================================================
+
+// a kind of problem a brush may have, that would break an exporter.
+#[derive(Copy, Clone, PartialEq)]
+pub enum ProblemKind {
+    // the brush is thinner than the micro-brush size.
+    Micro,
+    // the face has a repeated corner, or no area.
+    Degenerate,
+    // the corners of the face do not lie on one plane.
+    NonPlanar,
+    // the face points into the brush.
+    Inverted,
+    // a vertex of the brush is in front of a face.
+    Concave,
+}
+
+impl ProblemKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Micro => "Micro-Brush",
+            Self::Degenerate => "Degenerate Face",
+            Self::NonPlanar => "Non-Planar Face",
+            Self::Inverted => "Inverted Face",
+            Self::Concave => "Concave Brush",
+        }
+    }
+
+    // get every fix that may be used on the problem. the first one is the one used by "fix all".
+    pub fn fix_list(&self) -> &'static [ProblemFix] {
+        match self {
+            Self::Micro => &[ProblemFix::Delete],
+            Self::Degenerate => &[ProblemFix::Collapse, ProblemFix::Snap],
+            Self::NonPlanar => &[ProblemFix::Triangulate, ProblemFix::Snap],
+            Self::Inverted => &[ProblemFix::Flip],
+            Self::Concave => &[ProblemFix::Hull, ProblemFix::Snap, ProblemFix::Delete],
+        }
+    }
+}
+
+//================================================================
+
+// an automatic fix for a problem.
+#[derive(Copy, Clone, PartialEq)]
+pub enum ProblemFix {
+    // split the face into triangles.
+    Triangulate,
+    // weld every vertex that shares a point, and remove every face left without an area.
+    Collapse,
+    // reverse the corner order of the face.
+    Flip,
+    // snap every vertex of the brush to the grid.
+    Snap,
+    // replace the brush with its convex hull.
+    Hull,
+    // delete the brush.
+    Delete,
+}
+
+impl ProblemFix {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Triangulate => "Triangulate",
+            Self::Collapse => "Collapse",
+            Self::Flip => "Flip",
+            Self::Snap => "Snap to Grid",
+            Self::Hull => "Convex Hull",
+            Self::Delete => "Delete",
+        }
+    }
+}
+
+//================================================================
+
+// a problem found on a brush of the world, and on which face of it, if any.
+#[derive(Copy, Clone, PartialEq)]
+pub struct Problem {
+    pub brush: usize,
+    pub face: Option<usize>,
+    pub kind: ProblemKind,
+}
+
+impl Problem {
+    // find every problem in the world. a brush thinner than "micro" is a micro-brush.
+    pub fn find(world: &World, micro: f32) -> Vec<Self> {
+        let mut list = Vec::new();
+
+        for (i, brush) in world.brush.iter().enumerate() {
+            // a micro-brush is to be deleted anyway, whatever else is wrong with it.
+            if Self::thickness(brush) < micro {
+                list.push(Self::new(i, None, ProblemKind::Micro));
+                continue;
+            }
+
+            let count = list.len();
+            let center = Self::center(brush);
+
+            for (j, face) in brush.face.iter().enumerate() {
+                let point = brush.point(face);
+
+                let Some(plane) = Plane::new_from_list(&point) else {
+                    list.push(Self::new(i, Some(j), ProblemKind::Degenerate));
+                    continue;
+                };
+
+                if Self::area(&point) <= Plane::EPSILON || Self::distinct(&point) < point.len() {
+                    list.push(Self::new(i, Some(j), ProblemKind::Degenerate));
+                } else if point
+                    .iter()
+                    .any(|p| plane.distance_to(*p).abs() > Brush::CONVEX_EPSILON)
+                {
+                    list.push(Self::new(i, Some(j), ProblemKind::NonPlanar));
+                } else if plane.distance_to(center) > 0.0 {
+                    list.push(Self::new(i, Some(j), ProblemKind::Inverted));
+                }
+            }
+
+            // a bad face will also make the brush fail the convex check; only report it as concave if every face is fine.
+            if list.len() == count && !brush.convex() {
+                list.push(Self::new(i, None, ProblemKind::Concave));
+            }
+        }
+
+        list
+    }
+
+    fn new(brush: usize, face: Option<usize>, kind: ProblemKind) -> Self {
+        Self { brush, face, kind }
+    }
+
+    // get the name of the problem, alongside the brush and face it is on.
+    pub fn name(&self) -> String {
+        match self.face {
+            Some(face) => format!("Brush {}, Face {}: {}", self.brush, face, self.kind.name()),
+            None => format!("Brush {}: {}", self.brush, self.kind.name()),
+        }
+    }
+
+    // select the brush, and the face, of the problem, and nothing else.
+    pub fn select(&self, world: &mut World) {
+        world.select_none();
+
+        if let Some(brush) = world.brush.get(self.brush) {
+            world.selection.add(SelectionItem::Brush(self.brush));
+
+            if let Some(face) = self.face.filter(|i| *i < brush.face.len()) {
+                world.selection.add(SelectionItem::Face(self.brush, face));
+            }
+        }
+    }
+
+    // apply a fix to the problem. returns false if the fix could not be used.
+    pub fn fix(&self, world: &mut World, fix: ProblemFix, grid: f32) -> bool {
+        let Some(brush) = world.brush.get_mut(self.brush) else {
+            return false;
+        };
+
+        // a fix that rebuilds the vertex or face list of the brush must also deselect every part of it.
+        match fix {
+            ProblemFix::Triangulate => {
+                let Some(face) = self
+                    .face
+                    .filter(|i| brush.face.get(*i).is_some_and(|f| f.index.len() > 3))
+                else {
+                    return false;
+                };
+
+                Self::triangulate(brush, face);
+                world.selection.remove_part(self.brush);
+            }
+            ProblemFix::Collapse => {
+                Self::collapse(brush);
+                world.selection.remove_part(self.brush);
+            }
+            ProblemFix::Flip => {
+                let Some(face) = self.face.and_then(|i| brush.face.get_mut(i)) else {
+                    return false;
+                };
+
+                face.index.reverse();
+                brush.set_plane();
+            }
+            ProblemFix::Snap => {
+                for vertex in &mut brush.vertex {
+                    vertex.point = snap(&vertex.point, grid);
+                }
+
+                brush.set_plane();
+            }
+            ProblemFix::Hull => {
+                let Some((hull, _)) = Brush::merge(&[brush]) else {
+                    return false;
+                };
+
+                *brush = hull;
+                world.selection.remove_part(self.brush);
+            }
+            ProblemFix::Delete => {
+                world.remove_brush(self.brush);
+            }
+        }
+
+        true
+    }
+
+    // split a face into a fan of triangles. every corner is tried as the middle of the fan, and the first one that leaves
+    // the brush convex is used.
+    fn triangulate(brush: &mut Brush, face: usize) {
+        let source = brush.face.remove(face);
+        let count = source.index.len();
+        let mut first = None;
+
+        for start in 0..count {
+            let mut copy = brush.clone();
+
+            for i in 1..count - 1 {
+                let mut triangle = source.clone();
+                triangle.index = vec![
+                    source.index[start],
+                    source.index[(start + i) % count],
+                    source.index[(start + i + 1) % count],
+                ];
+                copy.face.push(triangle);
+            }
+
+            copy.set_plane();
+
+            if copy.convex() {
+                *brush = copy;
+                return;
+            }
+
+            first.get_or_insert(copy);
+        }
+
+        if let Some(copy) = first {
+            *brush = copy;
+        }
+    }
+
+    // weld every vertex that shares a point with another, then remove every repeated corner, every face without an area, and every unused vertex.
+    fn collapse(brush: &mut Brush) {
+        let mut point: Vec<Vector3> = Vec::new();
+        let mut remap = Vec::new();
+
+        for vertex in &brush.vertex {
+            match point
+                .iter()
+                .position(|p| p.distance_to(vertex.point) <= Plane::EPSILON)
+            {
+                Some(i) => remap.push(i),
+                None => {
+                    remap.push(point.len());
+                    point.push(vertex.point);
+                }
+            }
+        }
+
+        for face in &mut brush.face {
+            let mut index: Vec<usize> = face.index.iter().map(|i| remap[*i]).collect();
+            index.dedup();
+
+            while index.len() > 1 && index.first() == index.last() {
+                index.pop();
+            }
+
+            face.index = index;
+        }
+
+        brush.face.retain(|f| {
+            let list: Vec<Vector3> = f.index.iter().map(|i| point[*i]).collect();
+            f.index.len() >= 3 && Self::area(&list) > Plane::EPSILON
+        });
+
+        // only keep the vertices still in use.
+        let mut used = vec![None; point.len()];
+        let mut vertex = Vec::new();
+
+        for face in &mut brush.face {
+            for i in &mut face.index {
+                let p = point[*i];
+
+                *i = *used[*i].get_or_insert_with(|| {
+                    vertex.push(Vertex::new(p.x, p.y, p.z));
+                    vertex.len() - 1
+                });
+            }
+        }
+
+        brush.vertex = vertex;
+        brush.set_plane();
+    }
+
+    // get the area of a polygon.
+    fn area(point: &[Vector3]) -> f32 {
+        let mut normal = Vector3::zero();
+
+        for (i, a) in point.iter().enumerate() {
+            normal += a.cross(point[(i + 1) % point.len()]);
+        }
+
+        normal.length() * 0.5
+    }
+
+    // get the count of distinct corners of a polygon.
+    fn distinct(point: &[Vector3]) -> usize {
+        let mut list: Vec<Vector3> = Vec::new();
+
+        for p in point {
+            if !list.iter().any(|l| l.distance_to(*p) <= Plane::EPSILON) {
+                list.push(*p);
+            }
+        }
+
+        list.len()
+    }
+
+    // get the middle of every vertex of a brush.
+    fn center(brush: &Brush) -> Vector3 {
+        let sum = brush
+            .vertex
+            .iter()
+            .fold(Vector3::zero(), |a, v| a + v.point);
+
+        sum / brush.vertex.len().max(1) as f32
+    }
+
+    // get the thickness of a brush: the smallest distance across it, along the normal of any of its faces.
+    fn thickness(brush: &Brush) -> f32 {
+        brush
+            .face
+            .iter()
+            .filter_map(|f| Plane::new_from_list(&brush.point(f)))
+            .map(|plane| {
+                // an inverted face would point into the brush, so go by the spread of every vertex along the normal rather than by its side.
+                let (min, max) = brush
+                    .vertex
+                    .iter()
+                    .fold((f32::MAX, f32::MIN), |(min, max), v| {
+                        let distance = plane.distance_to(v.point);
+                        (min.min(distance), max.max(distance))
+                    });
+
+                max - min
+            })
+            .fold(f32::MAX, f32::min)
+    }
+}
+
+//================================================================
+
+// the problem list of the world, which is only found again once the world may have changed.
+#[derive(Default)]
+pub struct ProblemCache {
+    revision: Option<(usize, f32)>,
+    list: Vec<Problem>,
+}
+
+impl ProblemCache {
+    // get every problem in the world. "history" tells if the world may have changed since the last time.
+    pub fn get(&mut self, world: &World, history: &History, micro: f32) -> &[Problem] {
+        let revision = Some((history.revision(), micro));
+
+        if self.revision != revision {
+            self.list = Problem::find(world, micro);
+            self.revision = revision;
+        }
+
+        &self.list
+    }
+}
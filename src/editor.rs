@@ -15,8 +15,18 @@
 * PERFORMANCE OF THIS SOFTWARE.
 */
 
+use crate::check::*;
 use crate::game::*;
+use crate::geometry::*;
+use crate::glb::*;
 use crate::helper::*;
+use crate::map::*;
+use crate::marquee::*;
+use crate::obj::*;
+use crate::primitive::*;
+use crate::quake::*;
+use crate::selection::*;
+use crate::transform::*;
 use crate::window::*;
 
 //================================================================
@@ -25,7 +35,7 @@ use mlua::prelude::*;
 use raylib::{ffi::KeyboardKey::*, ffi::MouseButton::*, prelude::*};
 use serde::{de, de::Visitor, Deserialize, Serialize};
 use std::{
-    collections::{BTreeMap, HashMap},
+    collections::{BTreeMap, HashMap, VecDeque},
     ffi::CString,
     fmt,
 };
@@ -43,19 +53,32 @@ pub struct Editor {
     pub search_ent: String,
     pub search_tex: String,
     pub menu: bool,
+    pub path: Option<String>,
+    pub history: History,
+    pub recovery: Recovery,
+    pub problem: ProblemCache,
+    pub clip: Clip,
+    pub primitive: Primitive,
+    pub transform: Transform,
+    pub marquee: Marquee,
 }
 
 impl Editor {
+    // maximum number of fixes to apply at once with "fix all".
+    pub const CHECK_LIMIT: usize = 256;
+    // maximum number of problems listed in the check panel at once.
+    pub const CHECK_SHOW: usize = 32;
+
     #[rustfmt::skip]
-    pub fn new(handle: &mut RaylibHandle, thread: &RaylibThread, game: Game) -> Self {
+    pub fn new(handle: &mut RaylibHandle, thread: &RaylibThread, game: Game) -> Result<Self, String> {
         let mut asset = Asset::new(handle, thread);
-        let script = Script::new(&game)
-            .map_err(|e| panic(&e.to_string()))
-            .unwrap();
+        let script = Script::new(&game).map_err(|e| e.to_string())?;
 
-        asset.outer.set_texture_list(handle, thread, &script.meta.texture);
+        asset.outer.set_texture_list(handle, thread, &script.meta.texture)?;
 
-        Self {
+        let user = User::new();
+
+        Ok(Self {
             world: World::default(),
             widget: Widget::default(),
             asset,
@@ -65,24 +88,71 @@ impl Editor {
                 View::new(handle, thread, Camera3D::orthographic(Vector3::new(0.0, 512.0, 0.0), Vector3::new(0.0, 0.0, 0.0), Vector3::new(1.0, 0.0, 0.0), 15.0)),
                 View::new(handle, thread, Camera3D::orthographic(Vector3::new(0.0, 0.0, 512.0), Vector3::new(0.0, 0.0, 0.0), Vector3::new(0.0, 1.0, 0.0), 15.0)),
             ],
-            user: User::new(),
+            history: History::new(user.history),
+            user,
             script,
             game,
             search_ent: String::default(),
             search_tex: String::default(),
             menu: bool::default(),
-        }
+            path: None,
+            recovery: Recovery::new(),
+            problem: ProblemCache::default(),
+            clip: Clip::default(),
+            primitive: Primitive::default(),
+            transform: Transform::default(),
+            marquee: Marquee::default(),
+        })
+    }
+
+    // create a new editor, using the world from a map file.
+    pub fn new_from_map(
+        handle: &mut RaylibHandle,
+        thread: &RaylibThread,
+        game: Game,
+        map: Map,
+        path: String,
+    ) -> Result<Self, String> {
+        let mut editor = Self::new(handle, thread, game)?;
+
+        editor.world = map.world;
+        editor.set_path(path);
+
+        Ok(editor)
+    }
+
+    // create a new editor, using the world from an autosave snapshot. the map file the snapshot was made from is kept as the current one.
+    pub fn new_from_recovery(
+        handle: &mut RaylibHandle,
+        thread: &RaylibThread,
+        game: Game,
+        map: Map,
+        path: Option<String>,
+    ) -> Result<Self, String> {
+        let mut editor = Self::new(handle, thread, game)?;
+
+        editor.world = map.world;
+        editor.path = path;
+
+        Ok(editor)
     }
 
     pub fn select(
         user: &User,
+        asset: &Asset,
         world: &mut World,
+        history: &mut History,
         widget: &Widget,
+        marquee: &mut Marquee,
+        index: usize,
         draw: &mut RaylibDrawHandle,
         render_view: Rectangle,
         view: &Camera3D,
     ) {
         if user.interact.get_press(draw) {
+            // a new selection always begins a new step in the history.
+            history.end_group();
+
             // get ray from camera.
             let ray = draw.get_screen_to_world_ray_ex(
                 draw.get_mouse_position() - Vector2::new(render_view.x, render_view.y),
@@ -91,17 +161,27 @@ impl Editor {
                 render_view.height as i32,
             );
 
-            enum Picker {
-                Brush(usize),
-                Entity(usize),
-                Vertex(usize, usize),
-            }
-
-            let mut hit: Option<(Picker, f32)> = None;
+            let mut hit: Option<(SelectionItem, f32)> = None;
 
             // for each brush...
             for (i, brush) in world.brush.iter().enumerate() {
-                if brush.focus {
+                let focus = world.selection.brush(i);
+
+                if focus && matches!(widget, Widget::Edge) {
+                    // pick the edge closest to the ray.
+                    if let Some((edge, distance)) = brush.pick_edge(ray, Brush::PICK_RANGE) {
+                        if hit.as_ref().is_none_or(|(_, d)| distance < *d) {
+                            hit = Some((SelectionItem::Edge(i, edge), distance));
+                        }
+                    }
+                } else if focus && matches!(widget, Widget::Face) {
+                    // pick the face hit by the ray.
+                    if let Some((j, distance)) = brush.pick_face(ray) {
+                        if hit.as_ref().is_none_or(|(_, d)| distance < *d) {
+                            hit = Some((SelectionItem::Face(i, j), distance));
+                        }
+                    }
+                } else if focus {
                     // based on which widget is selected, do per vertex/per edge/per face picking.
                     for (j, vertex) in brush.vertex.iter().enumerate() {
                         // generate a bound-box.
@@ -117,35 +197,32 @@ impl Editor {
                         if ray.hit {
                             if let Some((_, distance)) = hit {
                                 if ray.distance < distance {
-                                    hit = Some((Picker::Vertex(i, j), ray.distance));
+                                    hit = Some((SelectionItem::Vertex(i, j), ray.distance));
                                 }
                             } else {
-                                hit = Some((Picker::Vertex(i, j), ray.distance));
+                                hit = Some((SelectionItem::Vertex(i, j), ray.distance));
                             }
                         }
                     }
                 } else {
                     for face in &brush.face {
-                        // generate quad.
-                        let point = [
-                            brush.vertex[face.index[0]].point,
-                            brush.vertex[face.index[1]].point,
-                            brush.vertex[face.index[2]].point,
-                            brush.vertex[face.index[3]].point,
-                        ];
-
-                        // check for collision.
-                        let ray =
-                            get_ray_collision_quad(ray, point[0], point[1], point[2], point[3]);
-
-                        // collision hit; check if the entity is closer than the hit entity, or if there is no hit entity, set it as such.
-                        if ray.hit {
-                            if let Some((_, distance)) = hit {
-                                if ray.distance < distance {
-                                    hit = Some((Picker::Brush(i), ray.distance));
+                        // generate polygon.
+                        let point = brush.point(face);
+
+                        // check for collision, with every triangle of the polygon.
+                        for j in 1..point.len().saturating_sub(1) {
+                            let ray =
+                                get_ray_collision_triangle(ray, point[0], point[j], point[j + 1]);
+
+                            // collision hit; check if the entity is closer than the hit entity, or if there is no hit entity, set it as such.
+                            if ray.hit {
+                                if let Some((_, distance)) = hit {
+                                    if ray.distance < distance {
+                                        hit = Some((SelectionItem::Brush(i), ray.distance));
+                                    }
+                                } else {
+                                    hit = Some((SelectionItem::Brush(i), ray.distance));
                                 }
-                            } else {
-                                hit = Some((Picker::Brush(i), ray.distance));
                             }
                         }
                     }
@@ -164,40 +241,37 @@ impl Editor {
                 if ray.hit {
                     if let Some((_, distance)) = hit {
                         if ray.distance < distance {
-                            hit = Some((Picker::Entity(i), ray.distance));
+                            hit = Some((SelectionItem::Entity(i), ray.distance));
                         }
                     } else {
-                        hit = Some((Picker::Entity(i), ray.distance));
+                        hit = Some((SelectionItem::Entity(i), ray.distance));
                     }
                 }
             }
 
             // collision!
-            if let Some(hit) = hit {
-                if !draw.is_key_down(KeyboardKey::KEY_LEFT_SHIFT) {
-                    world.select_all(false);
-                }
-
-                match hit.0 {
-                    Picker::Brush(i) => {
-                        world.select_all(false);
-                        let brush = world.brush.get_mut(i).unwrap();
-                        brush.focus = !brush.focus;
+            if let Some((item, _)) = hit {
+                // without a modifier, the picked part replaces the selection of its kind. picking a vertex, edge or face keeps
+                // every brush selected, so that the parts of more than one brush may be edited at once.
+                if user.select_subtract.get_down(draw) {
+                    world.selection.remove(item);
+                } else if user.select_add.get_down(draw) {
+                    world.selection.toggle(item);
+                } else {
+                    match item {
+                        SelectionItem::Brush(_) | SelectionItem::Entity(_) => world.select_none(),
+                        _ => world.selection.remove_part_all(),
                     }
-                    Picker::Entity(i) => {
-                        world.select_all(false);
 
-                        let entity = world.entity.get_mut(i).unwrap();
-                        entity.focus = !entity.focus;
-                    }
-                    Picker::Vertex(i, j) => {
-                        let brush = world.brush.get_mut(i).unwrap();
-                        brush.focus = true;
-                        brush.vertex[j].focus = !brush.vertex[j].focus;
-                    }
+                    world.selection.add(item);
                 }
             } else {
-                world.select_all(false);
+                // a click on nothing begins a marquee, which deselects everything if it is let go of without a drag.
+                marquee.begin(
+                    index,
+                    view,
+                    draw.get_mouse_position() - Vector2::new(render_view.x, render_view.y),
+                );
             }
         }
 
@@ -261,51 +335,110 @@ impl Editor {
 
         let zero = cross == Vector3::zero();
 
-        for entity in &mut world.entity {
-            if !entity.focus {
-                continue;
+        // a run of movement ends once every move key is let go of.
+        let moving = user.move_x_a.get_down(draw)
+            || user.move_x_b.get_down(draw)
+            || user.move_y_a.get_down(draw)
+            || user.move_y_b.get_down(draw);
+
+        if !moving && matches!(history.group(), Some(HistoryGroup::Transform(..))) {
+            history.end_group();
+        }
+
+        if !zero {
+            let transform = matches!(widget, Widget::Position | Widget::Rotation | Widget::Scale);
+
+            // a continuous run of movement, of the same selection with the same widget, is a single step in the history.
+            let selection = &world.selection;
+
+            if (transform && !selection.is_empty())
+                || selection
+                    .brush_list()
+                    .iter()
+                    .any(|i| selection.any_part(*i))
+            {
+                history.push_group(world, HistoryGroup::Transform(*widget, selection.clone()));
             }
+        }
 
-            if zero {
-                return;
+        if zero {
+            return;
+        }
+
+        // turn and scale around the middle of the selection, as the transform gizmo does.
+        let pivot = Transform::pivot(world).unwrap_or_default();
+
+        for (i, entity) in world.entity.iter_mut().enumerate() {
+            if !world.selection.entity(i) {
+                continue;
             }
 
             match widget {
-                Widget::Position => entity.position(cross),
-                Widget::Rotation => entity.rotation(cross),
-                Widget::Scale => entity.scale(cross),
+                Widget::Position => entity.position(cross * user.grid),
+                Widget::Rotation => entity.rotation(cross * user.angle_step, pivot),
+                Widget::Scale => entity.scale(cross * user.scale_step, pivot),
                 _ => {}
             }
         }
 
-        for brush in &mut world.brush {
-            if !brush.focus {
+        for (i, brush) in world.brush.iter_mut().enumerate() {
+            if !world.selection.brush(i) {
                 continue;
             }
 
-            if zero {
-                return;
-            }
+            // move every selected edge or face, along the view plane, or along their normal if the normal key is down.
+            if matches!(widget, Widget::Edge | Widget::Face) {
+                let before = brush.clone();
+                let along = user.normal.get_down(draw).then_some(y);
+
+                match widget {
+                    Widget::Face => brush.move_part(
+                        &world.selection.face_list(i),
+                        &[],
+                        cross * user.grid,
+                        along.map(|y| y * user.grid),
+                    ),
+                    _ => brush.move_part(
+                        &[],
+                        &world.selection.edge_list(i),
+                        cross * user.grid,
+                        along.map(|y| y * user.grid),
+                    ),
+                }
 
-            for vertex in &mut brush.vertex {
-                if !vertex.focus {
-                    continue;
+                // the move would break the brush, undo it.
+                if !brush.convex() {
+                    *brush = before;
+                } else if user.texture_lock {
+                    brush.lock(&before, asset);
                 }
 
-                vertex.point += cross;
+                continue;
             }
 
+            let before = brush.clone();
+
+            for j in world.selection.vertex_list(i) {
+                brush.vertex[j].point += cross * user.grid;
+            }
+
+            brush.set_plane();
+
             match widget {
-                Widget::Position => brush.position(cross),
-                Widget::Rotation => brush.rotation(cross),
-                Widget::Scale => brush.scale(cross),
+                Widget::Position => brush.position(cross * user.grid),
+                Widget::Rotation => brush.rotation(cross * user.angle_step, pivot),
+                Widget::Scale => brush.scale(Vector3::one() + cross * user.scale_step, pivot),
                 _ => {}
             }
+
+            if user.texture_lock {
+                brush.lock(&before, asset);
+            }
         }
     }
 
     #[rustfmt::skip]
-    pub fn update(&mut self, draw: &mut RaylibDrawHandle, thread: &RaylibThread, asset: &Asset) {
+    pub fn update(&mut self, draw: &mut RaylibDrawHandle, thread: &RaylibThread, asset: &Asset) -> Result<(), String> {
         if draw.is_window_resized() {
             self.view = [
                 View::new(draw, thread, Camera3D::perspective(Vector3::new(4.0, 4.0, 4.0), Vector3::new(0.0, 0.0, 0.0), Vector3::new(0.0, 1.0, 0.0), 90.0)),
@@ -324,16 +457,14 @@ impl Editor {
             );
 
             if draw.is_key_pressed(KeyboardKey::KEY_DELETE) {
-                let mut index: Vec<usize> = Vec::new();
+                let index = self.world.selection.entity_list();
 
-                for (j, entity) in self.world.entity.iter().enumerate() {
-                    if entity.focus {
-                        index.push(j);
-                    }
+                if !index.is_empty() {
+                    self.history.push(&self.world);
                 }
 
-                for (k, j) in index.iter().enumerate() {
-                    self.world.entity.remove(j - k);
+                for j in index.into_iter().rev() {
+                    self.world.remove_entity(j);
                 }
             }
 
@@ -362,21 +493,94 @@ impl Editor {
                 view.mouse = false;
             }
 
-            if render_view.check_collision_point_rec(draw.get_mouse_position()) {
-                Self::select(
-                    &self.user,
-                    &mut self.world,
-                    &self.widget,
-                    draw,
-                    render_view,
-                    &mut view.camera,
+            if render_view.check_collision_point_rec(draw.get_mouse_position()) && matches!(self.widget, Widget::Clip) {
+                if self.user.interact.get_press(draw) {
+                    let ray = draw.get_screen_to_world_ray_ex(
+                        draw.get_mouse_position() - Vector2::new(render_view.x, render_view.y),
+                        view.camera,
+                        render_view.width as i32,
+                        render_view.height as i32,
+                    );
+
+                    self.clip.pick(&self.world, &self.user, ray, &view.camera, i);
+                }
+
+                if self.user.look.get_press(draw) {
+                    view.mouse = true;
+                };
+            } else if render_view.check_collision_point_rec(draw.get_mouse_position()) {
+                let ray = draw.get_screen_to_world_ray_ex(
+                    draw.get_mouse_position() - Vector2::new(render_view.x, render_view.y),
+                    view.camera,
+                    render_view.width as i32,
+                    render_view.height as i32,
                 );
 
+                // grabbing a handle of the gizmo takes over from picking. the history step is only pushed
+                // once the handle is let go of, and only if the world was changed.
+                let grab = self.user.interact.get_press(draw) && self.transform.begin(&self.widget, &self.world, i, &view.camera, ray);
+
+                if !grab && !self.transform.active() {
+                    Self::select(
+                        &self.user,
+                        &self.asset,
+                        &mut self.world,
+                        &mut self.history,
+                        &self.widget,
+                        &mut self.marquee,
+                        i,
+                        draw,
+                        render_view,
+                        &view.camera,
+                    );
+                }
+
                 if self.user.look.get_press(draw) {
                     view.mouse = true;
                 };
             }
 
+            // drag the gizmo with the mouse, from the view it was grabbed in, even if the mouse has left it.
+            if self.transform.view() == Some(i) {
+                let ray = draw.get_screen_to_world_ray_ex(
+                    draw.get_mouse_position() - Vector2::new(render_view.x, render_view.y),
+                    view.camera,
+                    render_view.width as i32,
+                    render_view.height as i32,
+                );
+
+                self.transform.update(&self.widget, &mut self.world, &self.user, &self.asset, ray);
+
+                if !self.user.interact.get_down(draw) {
+                    if let Some(before) = self.transform.end() {
+                        if before != self.world {
+                            self.history.push(&before);
+                        }
+                    }
+                }
+            }
+
+            // drag the marquee with the mouse, from the view it was begun in.
+            if self.marquee.view() == Some(i) {
+                self.marquee.update(draw.get_mouse_position() - Vector2::new(render_view.x, render_view.y));
+
+                if !self.user.interact.get_down(draw) {
+                    let port = Vector2::new(render_view.width, render_view.height);
+
+                    self.marquee.end(&mut self.world, &self.user, &self.widget, draw, &view.camera, port);
+                }
+            }
+
+            // the ray under the mouse, for highlighting whatever would be picked.
+            let ray = render_view.check_collision_point_rec(draw.get_mouse_position()).then(|| {
+                draw.get_screen_to_world_ray_ex(
+                    draw.get_mouse_position() - Vector2::new(render_view.x, render_view.y),
+                    view.camera,
+                    render_view.width as i32,
+                    render_view.height as i32,
+                )
+            });
+
             {
                 let port = Vector2::new(
                     view.render_texture.width() as f32,
@@ -410,7 +614,7 @@ impl Editor {
                     }
                 };
 
-                draw_grid(1000, 1.0, angle);
+                draw_grid(1000, self.user.grid, angle);
 
                 let mut x = Ray::default();
                 x.direction = Vector3::new(1.0, 0.0, 0.0);
@@ -423,19 +627,21 @@ impl Editor {
                 draw.draw_ray(y, Color::GREEN);
                 draw.draw_ray(z, Color::BLUE);
 
-                for brush in &self.world.brush {
-                    brush.draw(&self.asset);
+                for (k, brush) in self.world.brush.iter().enumerate() {
+                    let selection = &self.world.selection;
+
+                    brush.draw(&self.asset, selection.brush(k));
 
-                    if brush.focus {
+                    if selection.brush(k) {
                         match self.widget {
                             Widget::Vertex => {
-                                for v in &brush.vertex {
+                                for (j, v) in brush.vertex.iter().enumerate() {
                                     draw.draw_cube(
                                         v.point,
                                         0.5,
                                         0.5,
                                         0.5,
-                                        if v.focus {
+                                        if selection.vertex(k, j) {
                                             Color::GREEN
                                         } else {
                                             Color::RED
@@ -444,17 +650,61 @@ impl Editor {
                                     );
                                 }
                             }
-                            Widget::Edge => {}
-                            Widget::Face => {}
+                            Widget::Edge => {
+                                let hover = ray.and_then(|ray| brush.pick_edge(ray, Brush::PICK_RANGE)).map(|(edge, _)| edge);
+
+                                for (a, b) in brush.edge_list() {
+                                    let color = {
+                                        if hover == Some((a, b)) {
+                                            Color::YELLOW
+                                        } else if selection.edge(k, (a, b)) {
+                                            Color::GREEN
+                                        } else {
+                                            Color::RED
+                                        }
+                                    };
+
+                                    draw.draw_line_3D(brush.vertex[a].point, brush.vertex[b].point, color);
+                                }
+                            }
+                            Widget::Face => {
+                                let hover = ray.and_then(|ray| brush.pick_face(ray)).map(|(face, _)| face);
+
+                                for (i, face) in brush.face.iter().enumerate() {
+                                    let color = {
+                                        if hover == Some(i) {
+                                            Color::YELLOW.alpha(0.5)
+                                        } else if selection.face(k, i) {
+                                            Color::GREEN.alpha(0.5)
+                                        } else {
+                                            continue;
+                                        }
+                                    };
+
+                                    // lift the polygon off of the face, so that it does not fight with it.
+                                    let point: Vec<Vector3> = brush.point(face).iter().map(|p| *p + face.plane.normal * 0.01).collect();
+
+                                    for j in 1..point.len().saturating_sub(1) {
+                                        draw.draw_triangle3D(point[0], point[j], point[j + 1], color);
+                                    }
+                                }
+                            }
                             _ => {}
                         }
                     }
                 }
 
-                for entity in &self.world.entity {
-                    entity.draw_3d(&self.script.lua, &mut draw);
+                if matches!(self.widget, Widget::Clip) {
+                    self.clip.update(&self.world, &self.history);
+                    self.clip.draw(&mut draw);
                 }
 
+                for (k, entity) in self.world.entity.iter().enumerate() {
+                    entity.draw_3d(&self.script.lua, &mut draw, self.world.selection.entity(k))?;
+                }
+
+                self.transform.draw(&self.widget, &self.world, &mut draw, &view.camera, ray);
+
                 drop(draw);
 
                 let mut draw = draw_texture.begin_mode2D(Camera2D {
@@ -464,9 +714,12 @@ impl Editor {
                     zoom: 1.0,
                 });
 
-                for entity in &self.world.entity {
-                    entity.draw_2d(&mut draw, asset, &view.camera, port);
+                for (k, entity) in self.world.entity.iter().enumerate() {
+                    entity.draw_2d(&mut draw, asset, &view.camera, port, self.world.selection.entity(k));
                 }
+
+                self.transform.draw_text(&mut draw, asset, &view.camera, port);
+                self.marquee.draw(&mut draw, i);
             }
 
             draw.draw_texture_rec(
@@ -481,45 +734,612 @@ impl Editor {
                 Color::WHITE,
             );
         }
+
+        self.autosave();
+
+        Ok(())
+    }
+
+    // write a snapshot of the world to the recovery folder, if it is time to.
+    pub fn autosave(&mut self) {
+        if let Err(e) = self.recovery.update(
+            &self.game,
+            &self.world,
+            &self.path,
+            self.user.autosave,
+            self.user.autosave_count,
+        ) {
+            error(&e);
+        }
+    }
+
+    // ask the user for a file to import the world from. the format is picked from the file extension.
+    pub fn import(&mut self) {
+        let path = rfd::FileDialog::new()
+            .add_filter(Map::FILE_INFO, &[Map::FILE_KIND])
+            .add_filter(Quake::FILE_INFO, &[Quake::FILE_KIND])
+            .pick_file();
+
+        if let Some(path) = path {
+            let kind = path
+                .extension()
+                .map(|kind| kind.to_string_lossy().to_lowercase());
+            let path = path.display().to_string();
+
+            match kind.as_deref() {
+                Some(Quake::FILE_KIND) => match Quake::new_from_file(&path) {
+                    Ok(quake) => {
+                        let (world, warning) =
+                            quake.to_world(&self.game, &self.asset, &self.script.meta.entity);
+
+                        self.history.push(&self.world);
+                        self.world = world;
+                        // the world no longer comes from a native map file, so do not overwrite the .map file on save.
+                        self.path = None;
+
+                        if !warning.is_empty() {
+                            error(&format!(
+                                "\"{path}\" was not imported in full.\n{}",
+                                warning.join("\n")
+                            ));
+                        }
+                    }
+                    Err(e) => error(&e),
+                },
+                _ => self.load(path),
+            }
+        }
+    }
+
+    // load a native map file.
+    pub fn load(&mut self, path: String) {
+        match Map::new_from_file(&path) {
+            Ok(map) => {
+                // the map was made for a different game, ask before loading it in.
+                if map.game != self.game.path {
+                    let result = rfd::MessageDialog::new()
+                        .set_level(rfd::MessageLevel::Warning)
+                        .set_title("Load Map")
+                        .set_description(format!(
+                            "\"{path}\" was made for the game at \"{}\". Load it anyway?",
+                            map.game
+                        ))
+                        .set_buttons(rfd::MessageButtons::YesNo)
+                        .show();
+
+                    if result != rfd::MessageDialogResult::Yes {
+                        return;
+                    }
+                }
+
+                self.history.push(&self.world);
+                self.world = map.world;
+                self.set_path(path);
+            }
+            Err(e) => error(&e),
+        }
+    }
+
+    // save the world to the current map file, or ask for one if there is none yet.
+    pub fn save(&mut self) {
+        if let Some(path) = &self.path {
+            if let Err(e) = Map::new(&self.game, &self.world).save_file(path) {
+                error(&e);
+            }
+        } else {
+            self.save_as();
+        }
+    }
+
+    // ask the user for a new map file, and save the world to it.
+    pub fn save_as(&mut self) {
+        if let Some(path) = Map::pick_save() {
+            match Map::new(&self.game, &self.world).save_file(&path) {
+                Ok(_) => self.set_path(path),
+                Err(e) => error(&e),
+            }
+        }
+    }
+
+    // ask the user for a file to export the world to. the format is picked from the file extension.
+    pub fn export(&mut self) {
+        let path = rfd::FileDialog::new()
+            .add_filter(Map::FILE_INFO, &[Map::FILE_KIND])
+            .add_filter(Quake::FILE_INFO, &[Quake::FILE_KIND])
+            .add_filter(Obj::FILE_INFO, &[Obj::FILE_KIND])
+            .add_filter(Glb::FILE_INFO, &[Glb::FILE_KIND])
+            .save_file();
+
+        if let Some(mut path) = path {
+            let kind = path
+                .extension()
+                .map(|kind| kind.to_string_lossy().to_lowercase());
+
+            let result = match kind.as_deref() {
+                Some(Quake::FILE_KIND) => {
+                    Quake::new_from_world(&self.world, &self.game, &self.asset)
+                        .save_file(&path.display().to_string())
+                }
+                Some(Obj::FILE_KIND) => {
+                    // OBJ has no notion of an entity, ask before writing them as named points.
+                    let entity = !self.world.entity.is_empty()
+                        && rfd::MessageDialog::new()
+                            .set_level(rfd::MessageLevel::Info)
+                            .set_title("Export")
+                            .set_description("Export every entity as a named point as well?")
+                            .set_buttons(rfd::MessageButtons::YesNo)
+                            .show()
+                            == rfd::MessageDialogResult::Yes;
+
+                    Obj::new_from_world(&self.world, &self.game, &self.asset, entity)
+                        .save_file(&path.display().to_string())
+                }
+                Some(Glb::FILE_KIND) => Glb::new_from_world(&self.world, &self.asset)
+                    .save_file(&path.display().to_string()),
+                _ => {
+                    if kind.is_none() {
+                        path.set_extension(Map::FILE_KIND);
+                    }
+
+                    let path = path.display().to_string();
+                    let result = Map::new(&self.game, &self.world).save_file(&path);
+
+                    if result.is_ok() {
+                        self.set_path(path);
+                    }
+
+                    result
+                }
+            };
+
+            if let Err(e) = result {
+                error(&e);
+            }
+        }
+    }
+
+    // add a new primitive to the world, selecting it.
+    pub fn primitive(&mut self, kind: PrimitiveKind) {
+        self.history.push(&self.world);
+        self.world.select_none();
+
+        // snap every length of the primitive to the grid, so that its bound-box lines up with it.
+        let mut primitive = self.primitive.clone();
+        primitive.radius = self.user.snap_length(primitive.radius);
+        primitive.height = self.user.snap_length(primitive.height);
+        primitive.thickness = self.user.snap_length(primitive.thickness);
+
+        // the thickness of an arch is kept at least a step of the grid below its radius, growing the radius if need be,
+        // so that its inner ring never collapses into the origin.
+        if matches!(kind, PrimitiveKind::Arch) {
+            let step = if self.user.snap_position {
+                self.user.grid
+            } else {
+                User::GRID_MIN
+            };
+
+            primitive.thickness = primitive.thickness.min(primitive.radius - step).max(step);
+            primitive.radius = primitive.radius.max(primitive.thickness + step);
+        }
+
+        for brush in primitive.create(kind) {
+            self.world.push_brush(brush);
+        }
+    }
+
+    // extrude every selected face outward into a new brush, by the extrude distance snapped to the grid. the new brush is selected,
+    // alongside its cap face, so that it may be extruded again.
+    pub fn extrude(&mut self) {
+        let distance = self.user.snap_length(self.user.extrude_distance);
+        let mut list = Vec::new();
+
+        for i in self.world.selection.brush_list() {
+            for j in self.world.selection.face_list(i) {
+                list.push(self.world.brush[i].extrude(j, distance));
+            }
+        }
+
+        if list.is_empty() {
+            return;
+        }
+
+        self.history.push(&self.world);
+        self.world.select_none();
+
+        for (brush, cap) in list {
+            let i = self.world.push_brush(brush);
+            self.world.selection.add(SelectionItem::Face(i, cap));
+        }
+    }
+
+    // grow every selected brush by moving every selected face outward, by the extrude distance snapped to the grid.
+    pub fn grow(&mut self) {
+        let distance = self.user.snap_length(self.user.extrude_distance);
+        let mut world = self.world.clone();
+
+        for i in world.selection.brush_list() {
+            let face = world.selection.face_list(i);
+
+            if face.is_empty() {
+                continue;
+            }
+
+            let brush = &mut world.brush[i];
+            let before = brush.clone();
+
+            brush.move_part(&face, &[], Vector3::zero(), Some(distance));
+
+            if !brush.convex() {
+                error("The selected faces can not be grown, as a brush would no longer be convex.");
+                return;
+            }
+
+            if self.user.texture_lock {
+                brush.lock(&before, &self.asset);
+            }
+        }
+
+        self.history.push(&self.world);
+        self.world = world;
+    }
+
+    // inset every selected face by the inset amount, raising it by the inset depth.
+    pub fn inset(&mut self) {
+        if self.user.inset_depth <= Plane::EPSILON {
+            error("The inset depth must be above zero, as every inset face would otherwise share its plane with the ring around it.");
+            return;
+        }
+
+        let mut world = self.world.clone();
+
+        for i in world.selection.brush_list() {
+            // the inset pushes new faces to the end of the list, so the index of every selected face stays the same.
+            for j in world.selection.face_list(i) {
+                if !world.brush[i].inset(j, self.user.inset_distance, self.user.inset_depth) {
+                    error(&format!(
+                        "A selected face is too small to be inset by {}.",
+                        self.user.inset_distance
+                    ));
+                    return;
+                }
+            }
+
+            if !world.brush[i].convex() {
+                error("The selected faces can not be inset, as a brush would no longer be convex.");
+                return;
+            }
+        }
+
+        self.history.push(&self.world);
+        self.world = world;
+    }
+
+    // split every selected brush along the cut of the clip tool.
+    pub fn clip(&mut self) {
+        if self.clip.plane().is_none() || self.world.selection.brush_list().is_empty() {
+            return;
+        }
+
+        self.history.push(&self.world);
+
+        let mut brush = Vec::new();
+
+        for (i, target) in self.world.brush.iter().enumerate() {
+            let focus = self.world.selection.brush(i);
+
+            match focus.then(|| self.clip.split(target)).flatten() {
+                Some(list) => brush.extend(list.into_iter().map(|piece| (piece, true))),
+                None => brush.push((target.clone(), focus)),
+            }
+        }
+
+        self.world.set_brush(brush);
+        self.clip.point.clear();
+    }
+
+    // line up the texture of every selected face with its edges.
+    pub fn face_align(&mut self, align: TextureAlign) {
+        let before = self.world.clone();
+
+        for (face, point) in self.world.face_focus_point() {
+            let shape = self.asset.texture_shape(&face.texture);
+            face.align(&point, shape, align);
+        }
+
+        // an align that changes nothing, such as on a texture already lined up, is not a step in the history.
+        if self.world != before {
+            self.history.push(&before);
+        }
+    }
+
+    // apply a fix to a problem found by the validity check.
+    pub fn check_fix(&mut self, problem: Problem, fix: ProblemFix) {
+        let before = self.world.clone();
+
+        if !problem.fix(&mut self.world, fix, self.user.grid) {
+            self.world = before;
+            error(&format!(
+                "{} could not be fixed with \"{}\".",
+                problem.name(),
+                fix.name()
+            ));
+            return;
+        }
+
+        self.history.push(&before);
+    }
+
+    // apply the first fix of every problem found by the validity check, until there are none left. a fix may bring up a new problem,
+    // such as a triangulated face leaving the brush concave, so the check is run again after every fix.
+    pub fn check_fix_all(&mut self) {
+        let before = self.world.clone();
+        let mut count = 0;
+
+        while let Some(problem) = Problem::find(&self.world, self.user.micro_brush)
+            .first()
+            .copied()
+        {
+            // give up on a problem that can not be fixed, rather than trying it forever.
+            if count >= Self::CHECK_LIMIT
+                || !problem.fix(&mut self.world, problem.kind.fix_list()[0], self.user.grid)
+            {
+                error(&format!("{} could not be fixed.", problem.name()));
+                break;
+            }
+
+            count += 1;
+        }
+
+        if count > 0 {
+            self.history.push(&before);
+        }
+    }
+
+    // merge every selected brush into their convex hull, unless it would add more volume than the tolerance allows.
+    pub fn merge(&mut self) {
+        let list: Vec<&Brush> = self
+            .world
+            .selection
+            .brush_list()
+            .into_iter()
+            .map(|i| &self.world.brush[i])
+            .collect();
+
+        if list.len() < 2 {
+            return;
+        }
+
+        let Some((hull, add)) = Brush::merge(&list) else {
+            error("The selected brushes can not be merged, as their convex hull has no volume.");
+            return;
+        };
+
+        let volume = hull.volume();
+
+        if add > volume * self.user.merge_tolerance {
+            error(&format!(
+                "The selected brushes can not be merged, as their convex hull would add {add:.2} units of volume ({:.1}% of the hull), over the tolerance of {:.1}%.",
+                add / volume * 100.0,
+                self.user.merge_tolerance * 100.0
+            ));
+            return;
+        }
+
+        self.history.push(&self.world);
+
+        let mut brush: Vec<(Brush, bool)> = self
+            .world
+            .brush
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| !self.world.selection.brush(*i))
+            .map(|(_, b)| (b.clone(), false))
+            .collect();
+
+        brush.push((hull, true));
+
+        self.world.set_brush(brush);
     }
 
-    pub fn reload(&mut self, handle: &mut RaylibHandle, thread: &RaylibThread) {
+    // hollow every selected brush out into a room.
+    pub fn hollow(&mut self) {
+        if self.world.selection.brush_list().is_empty() {
+            return;
+        }
+
+        // snap the thickness to the grid, never going below a single step of it.
+        let thickness = self.user.snap_length(self.user.hollow_thickness);
+
+        let mut face = Face::new(Vec::new());
+        face.texture = self.user.hollow_texture.clone();
+
+        let mut brush = Vec::new();
+
+        for (i, target) in self.world.brush.iter().enumerate() {
+            if !self.world.selection.brush(i) {
+                brush.push((target.clone(), false));
+                continue;
+            }
+
+            match target.hollow(thickness, &face) {
+                Some(list) => brush.extend(list.into_iter().map(|piece| (piece, true))),
+                None => {
+                    error(&format!("A brush is too thin to be hollowed out with a wall thickness of {thickness}."));
+                    return;
+                }
+            }
+        }
+
+        self.history.push(&self.world);
+        self.world.set_brush(brush);
+    }
+
+    // subtract every selected brush from every other brush it intersects.
+    pub fn carve(&mut self) {
+        let carve: Vec<Brush> = self
+            .world
+            .selection
+            .brush_list()
+            .into_iter()
+            .map(|i| self.world.brush[i].clone())
+            .collect();
+
+        if carve.is_empty() {
+            return;
+        }
+
+        let mut brush = Vec::new();
+        let mut change = false;
+
+        for (i, target) in self.world.brush.iter().enumerate() {
+            if self.world.selection.brush(i) {
+                brush.push((target.clone(), true));
+                continue;
+            }
+
+            let mut piece = vec![target.clone()];
+
+            for other in &carve {
+                piece = piece
+                    .into_iter()
+                    .flat_map(|p| match p.carve(other) {
+                        Some(list) => {
+                            change = true;
+                            list
+                        }
+                        None => vec![p],
+                    })
+                    .collect();
+            }
+
+            brush.extend(piece.into_iter().map(|p| (p, false)));
+        }
+
+        if change {
+            self.history.push(&self.world);
+            self.world.set_brush(brush);
+        }
+    }
+
+    // set the current map file, and push it to the recent list.
+    fn set_path(&mut self, path: String) {
+        if let Err(e) = Recent::push(&path) {
+            error(&e);
+        }
+
+        self.path = Some(path);
+    }
+
+    // reload every Lua script and texture from the game.
+    pub fn reload(
+        &mut self,
+        handle: &mut RaylibHandle,
+        thread: &RaylibThread,
+    ) -> Result<(), String> {
+        self.script = Script::new(&self.game).map_err(|e| e.to_string())?;
         self.asset.outer.texture.clear();
-        self.script = Script::new(&self.game)
-            .map_err(|e| panic(&e.to_string()))
-            .unwrap();
         self.asset
             .outer
-            .set_texture_list(handle, thread, &self.script.meta.texture);
+            .set_texture_list(handle, thread, &self.script.meta.texture)
     }
 }
 
 //================================================================
 
-#[derive(Deserialize, Serialize)]
+#[derive(Clone, PartialEq, Deserialize, Serialize)]
 pub struct World {
     pub brush: Vec<Brush>,
     pub entity: Vec<Entity>,
+    #[serde(skip)]
+    pub selection: Selection,
 }
 
 impl World {
-    pub fn select_all(&mut self, value: bool) {
-        for brush in &mut self.brush {
-            brush.focus = value;
+    // get every selected face of every selected brush, or every face of them if no face is selected.
+    pub fn face_focus(&mut self) -> Vec<&mut Face> {
+        self.face_focus_point()
+            .into_iter()
+            .map(|(face, _)| face)
+            .collect()
+    }
 
-            for vertex in &mut brush.vertex {
-                vertex.focus = value;
+    // get every selected face, as in "face_focus", alongside the point of every corner of it.
+    pub fn face_focus_point(&mut self) -> Vec<(&mut Face, Vec<Vector3>)> {
+        let selection = &self.selection;
+        let any = selection
+            .brush_list()
+            .iter()
+            .any(|i| !selection.face_list(*i).is_empty());
+        let mut list = Vec::new();
+
+        for (i, brush) in self.brush.iter_mut().enumerate() {
+            if !selection.brush(i) {
+                continue;
             }
 
-            for face in &mut brush.face {
-                face.focus = value;
+            for (j, face) in brush.face.iter_mut().enumerate() {
+                if any && !selection.face(i, j) {
+                    continue;
+                }
+
+                let point = face.index.iter().map(|i| brush.vertex[*i].point).collect();
+                list.push((face, point));
             }
         }
 
-        for entity in &mut self.entity {
-            entity.focus = value;
+        list
+    }
+
+    // select every part of a kind.
+    pub fn select_all(&mut self, kind: SelectionKind) {
+        let mut selection = std::mem::take(&mut self.selection);
+        selection.select_all(self, kind);
+        self.selection = selection;
+    }
+
+    // select every part of a kind that is not selected, and deselect every one that is.
+    pub fn select_invert(&mut self, kind: SelectionKind) {
+        let mut selection = std::mem::take(&mut self.selection);
+        selection.invert(self, kind);
+        self.selection = selection;
+    }
+
+    // deselect everything.
+    pub fn select_none(&mut self) {
+        self.selection.select_none();
+    }
+
+    // remove a brush, keeping the selection in order.
+    pub fn remove_brush(&mut self, i: usize) -> Brush {
+        self.selection.remove_brush(i);
+        self.brush.remove(i)
+    }
+
+    // remove an entity, keeping the selection in order.
+    pub fn remove_entity(&mut self, i: usize) -> Entity {
+        self.selection.remove_entity(i);
+        self.entity.remove(i)
+    }
+
+    // replace every brush, selecting every one marked as such. every other brush is deselected, alongside every vertex,
+    // edge and face, as the new brush list no longer lines up with them. the list may be shorter than before.
+    pub fn set_brush(&mut self, list: Vec<(Brush, bool)>) {
+        self.selection.remove_brush_all();
+        self.brush.clear();
+
+        for (i, (brush, focus)) in list.into_iter().enumerate() {
+            self.brush.push(brush);
+            self.selection.set(SelectionItem::Brush(i), focus);
         }
     }
+
+    // add a brush, selecting it. returns its index.
+    pub fn push_brush(&mut self, brush: Brush) -> usize {
+        self.brush.push(brush);
+        self.selection
+            .add(SelectionItem::Brush(self.brush.len() - 1));
+        self.brush.len() - 1
+    }
 }
 
 impl Default for World {
@@ -527,21 +1347,581 @@ impl Default for World {
         Self {
             brush: vec![Brush::default()],
             entity: vec![],
+            selection: Selection::default(),
         }
     }
 }
 
 //================================================================
 
-#[derive(Deserialize, Serialize)]
+// a continuous edit, which is a single step in the history for as long as it is of the same target.
+#[derive(Clone, PartialEq)]
+pub enum HistoryGroup {
+    // a run of movement with the keyboard, with a widget, of a selection.
+    Transform(Widget, Selection),
+    // an edit of an entity in the entity panel.
+    Entity(usize),
+    // an edit of the texture of every selected face, in the texture panel.
+    Face(Selection),
+}
+
+// a history of every edit made to the world, for undo and redo. every step is a snapshot of the world before the edit.
+pub struct History {
+    undo: VecDeque<World>,
+    redo: Vec<World>,
+    group: Option<HistoryGroup>,
+    depth: usize,
+    // count of every edit, undo and redo, so that anything worked out from the world knows when it may have changed.
+    revision: usize,
+}
+
+impl History {
+    pub fn new(depth: usize) -> Self {
+        Self {
+            undo: VecDeque::new(),
+            redo: Vec::new(),
+            group: None,
+            depth,
+            revision: 0,
+        }
+    }
+
+    // record the world as it was before an edit.
+    pub fn push(&mut self, world: &World) {
+        self.group = None;
+        self.record(world);
+    }
+
+    // record the world as it was before an edit, unless the last edit was of the same group, so that a continuous edit (such as a drag) is a single step.
+    pub fn push_group(&mut self, world: &World, group: HistoryGroup) {
+        self.revision += 1;
+
+        if self.group.as_ref() != Some(&group) {
+            self.record(world);
+            self.group = Some(group);
+        }
+    }
+
+    // end the current group, so that the next edit will be a new step.
+    pub fn end_group(&mut self) {
+        self.group = None;
+    }
+
+    // get the current group, if any.
+    pub fn group(&self) -> Option<&HistoryGroup> {
+        self.group.as_ref()
+    }
+
+    // get the count of every edit, undo and redo so far. the world may have changed whenever it goes up.
+    pub fn revision(&self) -> usize {
+        self.revision
+    }
+
+    // go back one step. returns false if there is no step to go back to.
+    pub fn undo(&mut self, world: &mut World) -> bool {
+        if let Some(step) = self.undo.pop_back() {
+            self.redo.push(std::mem::replace(world, step));
+            self.group = None;
+            self.revision += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    // go forward one step. returns false if there is no step to go forward to.
+    pub fn redo(&mut self, world: &mut World) -> bool {
+        if let Some(step) = self.redo.pop() {
+            self.undo.push_back(std::mem::replace(world, step));
+            self.group = None;
+            self.revision += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn record(&mut self, world: &World) {
+        self.revision += 1;
+        self.redo.clear();
+        self.undo.push_back(world.clone());
+
+        while self.undo.len() > self.depth {
+            self.undo.pop_front();
+        }
+    }
+}
+
+//================================================================
+
+#[derive(Clone, PartialEq, Deserialize, Serialize)]
 pub struct Brush {
-    pub vertex: [Vertex; 8],
-    pub face: [Face; 6],
-    pub focus: bool,
+    pub vertex: Vec<Vertex>,
+    pub face: Vec<Face>,
 }
 
 impl Brush {
     pub const DEFAULT_SHAPE: f32 = 1.0;
+    // maximum distance from the ray to an edge, for it to be picked.
+    pub const PICK_RANGE: f32 = 0.25;
+    // maximum distance a vertex may be in front of a face, for the brush to still be convex.
+    pub const CONVEX_EPSILON: f32 = 0.01;
+
+    // create a brush from a point list and a face list. the plane of every face is set from the point list.
+    pub fn new(point: &[Vector3], face: Vec<Face>) -> Self {
+        let mut brush = Self {
+            vertex: point.iter().map(|p| Vertex::new(p.x, p.y, p.z)).collect(),
+            face,
+        };
+
+        brush.set_plane();
+        brush
+    }
+
+    // get the point of every corner of a face.
+    pub fn point(&self, face: &Face) -> Vec<Vector3> {
+        face.index.iter().map(|i| self.vertex[*i].point).collect()
+    }
+
+    // re-calculate the plane of every face. must be called after any vertex has been moved.
+    pub fn set_plane(&mut self) {
+        for i in 0..self.face.len() {
+            if let Some(plane) = Plane::new_from_list(&self.point(&self.face[i])) {
+                self.face[i].plane = plane;
+            }
+        }
+    }
+
+    // create a brush enclosed by the back side of every plane. every plane comes with the face it should make, whose index list is replaced,
+    // and the corners that face had before, if any, so that its texture stays where it was on the surface.
+    // returns None if the planes do not enclose a volume.
+    pub fn new_from_plane(template: &[(Plane, Face, Vec<Vector3>)]) -> Option<Self> {
+        let plane: Vec<Plane> = template.iter().map(|(p, _, _)| *p).collect();
+        let polyhedron = Polyhedron::new_from_plane(&plane)?;
+        let source: Vec<usize> = polyhedron.face.iter().map(|(i, _)| *i).collect();
+
+        let face = polyhedron
+            .face
+            .into_iter()
+            .map(|(i, index)| {
+                let mut face = template[i].1.clone();
+                face.index = index;
+                face
+            })
+            .collect();
+
+        let mut brush = Self::new(&polyhedron.vertex, face);
+
+        // a fit texture runs along the first edge, so the face must start on the edge that runs the most like the one it started on before,
+        // and the texture must then be fit again. a projected texture is laid from the plane, so it stays where it was on its own.
+        for (j, i) in source.into_iter().enumerate() {
+            let before = &template[i].2;
+
+            if brush.face[j].projection != Projection::Fit || before.len() < 2 {
+                continue;
+            }
+
+            let direction = (before[1] - before[0]).normalized();
+            let point = brush.point(&brush.face[j]);
+            let count = point.len();
+            let edge = |k: usize| {
+                (point[(k + 1) % count] - point[k])
+                    .normalized()
+                    .dot(direction)
+            };
+
+            if let Some(start) = (0..count).max_by(|a, b| edge(*a).total_cmp(&edge(*b))) {
+                brush.face[j].index.rotate_left(start);
+            }
+
+            let after = brush.point(&brush.face[j]);
+            brush.face[j].refit(before, &after);
+        }
+
+        Some(brush)
+    }
+
+    // get the plane of every face, alongside the face itself and its corners.
+    pub fn plane_list(&self) -> Vec<(Plane, Face, Vec<Vector3>)> {
+        self.face
+            .iter()
+            .map(|f| (f.plane, f.clone(), self.point(f)))
+            .collect()
+    }
+
+    // split the brush along a plane, giving back the part behind it and the part in front of it, if any. "face" is used for the new cap face.
+    pub fn split(&self, plane: Plane, face: &Face) -> (Option<Self>, Option<Self>) {
+        let distance: Vec<f32> = self
+            .vertex
+            .iter()
+            .map(|v| plane.distance_to(v.point))
+            .collect();
+
+        // the brush is entirely on one side: keep it as it is.
+        if distance.iter().all(|d| *d <= Plane::EPSILON) {
+            return (Some(self.clone()), None);
+        }
+        if distance.iter().all(|d| *d >= -Plane::EPSILON) {
+            return (None, Some(self.clone()));
+        }
+
+        let mut back = self.plane_list();
+        let mut front = back.clone();
+
+        back.push((plane, face.clone(), Vec::new()));
+        front.push((plane.flip(), face.clone(), Vec::new()));
+
+        (Self::new_from_plane(&back), Self::new_from_plane(&front))
+    }
+
+    // subtract another brush from this one, giving back the convex pieces that are left, or None if the two do not intersect.
+    // the pieces keep every face of this brush, and each new face is a copy of the face of the other brush it was cut with.
+    pub fn carve(&self, other: &Brush) -> Option<Vec<Self>> {
+        let mut plane: Vec<Plane> = self.face.iter().map(|f| f.plane).collect();
+        plane.extend(other.face.iter().map(|f| f.plane));
+
+        // the shared volume must be more than a face or an edge.
+        Polyhedron::new_from_plane(&plane)?;
+
+        let mut rest = self.clone();
+        let mut piece = Vec::new();
+
+        // peel off whatever is in front of every plane of the other brush. whatever is left at the end is inside of it.
+        for face in &other.face {
+            let (back, front) = rest.split(face.plane, face);
+
+            if let Some(front) = front {
+                piece.push(front);
+            }
+
+            match back {
+                Some(back) => rest = back,
+                None => break,
+            }
+        }
+
+        Some(piece)
+    }
+
+    // get every edge, as a pair of vertex indices, lowest first.
+    pub fn edge_list(&self) -> Vec<(usize, usize)> {
+        let mut list = Vec::new();
+
+        for face in &self.face {
+            for (i, a) in face.index.iter().enumerate() {
+                let b = face.index[(i + 1) % face.index.len()];
+                let edge = (*a.min(&b), *a.max(&b));
+
+                if !list.contains(&edge) {
+                    list.push(edge);
+                }
+            }
+        }
+
+        list
+    }
+
+    // get the edge closest to a ray, alongside the distance along the ray, if the ray runs within range of it.
+    pub fn pick_edge(&self, ray: Ray, range: f32) -> Option<((usize, usize), f32)> {
+        let mut hit: Option<((usize, usize), f32, f32)> = None;
+
+        for (a, b) in self.edge_list() {
+            let point = self.vertex[a].point;
+            let u = self.vertex[b].point - point;
+            let w = point - ray.position;
+
+            // closest point between the ray and the edge.
+            let uu = u.dot(u);
+            let ud = u.dot(ray.direction);
+            let dd = ray.direction.dot(ray.direction);
+            let den = uu * dd - ud * ud;
+
+            let s = {
+                if den > f32::EPSILON {
+                    ((ud * ray.direction.dot(w) - dd * u.dot(w)) / den).clamp(0.0, 1.0)
+                } else {
+                    0.0
+                }
+            };
+
+            let point = point + u * s;
+            let t = ((point - ray.position).dot(ray.direction) / dd).max(0.0);
+            let range_to = (point - (ray.position + ray.direction * t)).length();
+
+            // on a tie, such as two edges lined up in an orthographic view, pick the closest one along the ray.
+            let closer = hit.is_none_or(|(_, d, r)| {
+                range_to < r - Plane::EPSILON || ((range_to - r).abs() <= Plane::EPSILON && t < d)
+            });
+
+            if range_to <= range && closer {
+                hit = Some(((a, b), t, range_to));
+            }
+        }
+
+        hit.map(|(edge, distance, _)| (edge, distance))
+    }
+
+    // get the face closest along a ray, alongside the distance along the ray, checking every triangle of the polygon.
+    pub fn pick_face(&self, ray: Ray) -> Option<(usize, f32)> {
+        let mut hit: Option<(usize, f32)> = None;
+
+        for (i, face) in self.face.iter().enumerate() {
+            let point = self.point(face);
+
+            for j in 1..point.len().saturating_sub(1) {
+                let ray = get_ray_collision_triangle(ray, point[0], point[j], point[j + 1]);
+
+                if ray.hit && hit.is_none_or(|(_, d)| ray.distance < d) {
+                    hit = Some((i, ray.distance));
+                }
+            }
+        }
+
+        hit
+    }
+
+    // move a list of faces, or else a list of edges. if "along" is set, every face or edge moves along its own normal by that
+    // much instead, where the normal of an edge is the average normal of the faces around it.
+    pub fn move_part(
+        &mut self,
+        face: &[usize],
+        edge: &[(usize, usize)],
+        value: Vector3,
+        along: Option<f32>,
+    ) {
+        let mut normal: Vec<Vec<Vector3>> = vec![Vec::new(); self.vertex.len()];
+        let is_face = !face.is_empty();
+
+        if is_face {
+            for f in face.iter().filter_map(|i| self.face.get(*i)) {
+                for i in &f.index {
+                    normal[*i].push(f.plane.normal);
+                }
+            }
+        } else {
+            for (a, b) in edge.iter().copied() {
+                if a >= self.vertex.len() || b >= self.vertex.len() {
+                    continue;
+                }
+
+                let n = self
+                    .face
+                    .iter()
+                    .filter(|f| f.index.contains(&a) && f.index.contains(&b))
+                    .fold(Vector3::zero(), |n, f| n + f.plane.normal)
+                    .normalized();
+
+                normal[a].push(n);
+                normal[b].push(n);
+            }
+        }
+
+        for (vertex, normal) in self.vertex.iter_mut().zip(normal) {
+            if normal.is_empty() {
+                continue;
+            }
+
+            vertex.point += match along {
+                // faces add up their normal, so that a corner moves out with every face around it; edges average it.
+                Some(along) if is_face => {
+                    normal.iter().fold(Vector3::zero(), |a, n| a + *n) * along
+                }
+                Some(along) => {
+                    normal
+                        .iter()
+                        .fold(Vector3::zero(), |a, n| a + *n)
+                        .normalized()
+                        * along
+                }
+                None => value,
+            };
+        }
+
+        self.set_plane();
+    }
+
+    // extrude a face outward into a new brush. the new side faces copy the face of this brush on the other side of their edge.
+    // gives back the new brush, alongside the index of its cap face.
+    pub fn extrude(&self, face: usize, distance: f32) -> (Self, usize) {
+        let source = &self.face[face];
+        let count = source.index.len();
+        let shift = source.plane.normal * distance;
+
+        let mut point = self.point(source);
+        point.extend(self.point(source).iter().map(|p| *p + shift));
+
+        let mut list = Vec::new();
+
+        // the base face, facing back into this brush, and the cap face.
+        let mut base = source.clone();
+        base.index = (0..count).rev().collect();
+        list.push(base);
+
+        let mut cap = source.clone();
+        cap.index = (count..count * 2).collect();
+        list.push(cap);
+        let cap = list.len() - 1;
+
+        for i in 0..count {
+            let j = (i + 1) % count;
+            let (a, b) = (source.index[i], source.index[j]);
+
+            let mut side = self
+                .face
+                .iter()
+                .enumerate()
+                .find(|(k, f)| *k != face && f.index.contains(&a) && f.index.contains(&b))
+                .map(|(_, f)| f.clone())
+                .unwrap_or(source.clone());
+
+            side.index = vec![i, j, count + j, count + i];
+            list.push(side);
+        }
+
+        (Self::new(&point, list), cap)
+    }
+
+    // inset a face, replacing it with a smaller face in the middle and a ring of faces around it, every one a copy of the face.
+    // the middle face is raised by "depth", as a ring on the plane of the face would leave the brush with more than one face on it.
+    // returns false if the face is too small for the inset.
+    pub fn inset(&mut self, face: usize, amount: f32, depth: f32) -> bool {
+        let source = self.face[face].clone();
+        let point = self.point(&source);
+        let normal = source.plane.normal;
+        let count = point.len();
+
+        let mut inner = Vec::new();
+
+        // move every corner inward, so that every edge moves by the same amount.
+        for i in 0..count {
+            let a = point[(i + count - 1) % count];
+            let b = point[i];
+            let c = point[(i + 1) % count];
+
+            let in_a = normal.cross(b - a).normalized();
+            let in_b = normal.cross(c - b).normalized();
+            let den = 1.0 + in_a.dot(in_b);
+
+            if den <= f32::EPSILON {
+                return false;
+            }
+
+            inner.push(b + (in_a + in_b) * (amount / den) + normal * depth);
+        }
+
+        // every inner edge must still run the same way as the outer edge, otherwise the inset went past the middle.
+        for i in 0..count {
+            let j = (i + 1) % count;
+
+            if (inner[j] - inner[i]).dot(point[j] - point[i]) <= Plane::EPSILON {
+                return false;
+            }
+        }
+
+        let start = self.vertex.len();
+
+        self.vertex
+            .extend(inner.iter().map(|p| Vertex::new(p.x, p.y, p.z)));
+
+        self.face[face].index = (start..start + count).collect();
+
+        for i in 0..count {
+            let j = (i + 1) % count;
+            let mut ring = source.clone();
+            ring.index = vec![source.index[i], source.index[j], start + j, start + i];
+            self.face.push(ring);
+        }
+
+        self.set_plane();
+
+        true
+    }
+
+    // check if the brush is still a convex solid: every face must be flat, and every vertex must be on or behind the plane of every face.
+    pub fn convex(&self) -> bool {
+        self.face.iter().all(|f| {
+            self.point(f)
+                .iter()
+                .all(|p| f.plane.distance_to(*p).abs() <= Self::CONVEX_EPSILON)
+                && self
+                    .vertex
+                    .iter()
+                    .all(|v| f.plane.distance_to(v.point) <= Self::CONVEX_EPSILON)
+        }) && self.volume() > Self::CONVEX_EPSILON
+    }
+
+    // get the volume of the brush.
+    pub fn volume(&self) -> f32 {
+        let point: Vec<Vector3> = self.vertex.iter().map(|v| v.point).collect();
+
+        Polyhedron::volume_list(&point, self.face.iter().map(|f| f.index.as_slice()))
+    }
+
+    // merge a list of brushes into their convex hull. every face of the hull is a copy of the face of a brush on the same plane, or
+    // of the face that points the most in the same direction otherwise. gives back the hull, alongside the volume it would add.
+    pub fn merge(list: &[&Brush]) -> Option<(Self, f32)> {
+        let point: Vec<Vector3> = list
+            .iter()
+            .flat_map(|b| b.vertex.iter().map(|v| v.point))
+            .collect();
+        let face: Vec<(&Face, Vec<Vector3>)> = list
+            .iter()
+            .flat_map(|b| b.face.iter().map(|f| (f, b.point(f))))
+            .collect();
+
+        // a face on the same plane keeps its texture where it was, a face from another plane is only copied.
+        let template: Vec<(Plane, Face, Vec<Vector3>)> = Polyhedron::hull_plane(&point)
+            .into_iter()
+            .filter_map(|plane| {
+                if let Some((face, point)) = face.iter().find(|(f, _)| f.plane.equal(&plane)) {
+                    return Some((plane, (*face).clone(), point.clone()));
+                }
+
+                let (face, _) = face.iter().max_by(|(a, _), (b, _)| {
+                    a.plane
+                        .normal
+                        .dot(plane.normal)
+                        .total_cmp(&b.plane.normal.dot(plane.normal))
+                })?;
+
+                Some((plane, (*face).clone(), Vec::new()))
+            })
+            .collect();
+
+        let hull = Self::new_from_plane(&template)?;
+
+        // carve every brush out of the hull; whatever is left over is the volume the hull would add.
+        let mut rest = vec![hull.clone()];
+
+        for brush in list {
+            rest = rest
+                .into_iter()
+                .flat_map(|p| p.carve(brush).unwrap_or(vec![p]))
+                .collect();
+        }
+
+        let add = rest.iter().map(|b| b.volume()).sum();
+
+        Some((hull, add))
+    }
+
+    // hollow the brush out into a room, giving back a wall brush for every face, of the given thickness. every inner face is a copy of "face".
+    // returns None if the brush is too thin for the walls.
+    pub fn hollow(&self, thickness: f32, face: &Face) -> Option<Vec<Self>> {
+        let inner: Vec<(Plane, Face, Vec<Vector3>)> = self
+            .face
+            .iter()
+            .map(|f| {
+                (
+                    Plane::new(f.plane.normal, f.plane.distance - thickness),
+                    face.clone(),
+                    Vec::new(),
+                )
+            })
+            .collect();
+
+        self.carve(&Self::new_from_plane(&inner)?)
+    }
 
     pub fn position(&mut self, value: Vector3) {
         for v in &mut self.vertex {
@@ -549,30 +1929,68 @@ impl Brush {
                 .point
                 .transform_with(Matrix::translate(value.x, value.y, value.z));
         }
+
+        self.set_plane();
     }
 
-    pub fn rotation(&mut self, value: Vector3) {
+    // turn the brush around a point, by an angle in degrees on every axis.
+    pub fn rotation(&mut self, value: Vector3, pivot: Vector3) {
+        let matrix = Matrix::rotate_xyz(value * DEG2RAD as f32);
+
         for v in &mut self.vertex {
-            v.point = v
-                .point
-                .transform_with(Matrix::rotate_xyz(value * DEG2RAD as f32 * 10.0));
+            v.point = pivot + (v.point - pivot).transform_with(matrix);
         }
+
+        self.set_plane();
     }
 
-    pub fn scale(&mut self, value: Vector3) {
+    // scale the brush away from a point.
+    pub fn scale(&mut self, value: Vector3, pivot: Vector3) {
+        let matrix = Matrix::scale(value.x, value.y, value.z);
+
         for v in &mut self.vertex {
-            v.point = v
-                .point
-                .transform_with(Matrix::scale(value.x, value.y, value.z));
+            v.point = pivot + (v.point - pivot).transform_with(matrix);
+        }
+
+        self.set_plane();
+    }
+
+    // keep the texture of every face where it was on the surface before a transform, rather than fitting it to the face again.
+    // "before" must be this brush, as it was before the transform.
+    pub fn lock(&mut self, before: &Brush, asset: &Asset) {
+        for (face, other) in self.face.iter_mut().zip(&before.face) {
+            let point: Vec<Vector3> = face.index.iter().map(|i| self.vertex[*i].point).collect();
+
+            face.lock(
+                &before.point(other),
+                &point,
+                asset.texture_shape(&face.texture),
+            );
+        }
+    }
+
+    // draw the edge of every face.
+    pub fn draw_wire(
+        &self,
+        draw: &mut RaylibMode3D<RaylibTextureMode<RaylibDrawHandle>>,
+        color: Color,
+    ) {
+        for f in &self.face {
+            let point = self.point(f);
+
+            for (i, a) in point.iter().enumerate() {
+                draw.draw_line_3D(*a, point[(i + 1) % point.len()], color);
+            }
         }
     }
 
-    pub fn draw(&self, asset: &Asset) {
+    // draw the brush, tinted if it is selected.
+    pub fn draw(&self, asset: &Asset, focus: bool) {
         unsafe {
-            // begin quad draw.
-            ffi::rlBegin(ffi::RL_QUADS.try_into().unwrap());
+            // begin triangle draw.
+            ffi::rlBegin(ffi::RL_TRIANGLES.try_into().unwrap());
 
-            if self.focus {
+            if focus {
                 ffi::rlColor3f(1.00, 0.75, 0.75);
             } else {
                 ffi::rlColor3f(1.00, 1.00, 1.00);
@@ -593,33 +2011,19 @@ impl Brush {
                     ffi::rlSetTexture(asset.inner.default.id);
                 }
 
-                ffi::rlTexCoord2f(f.scale.x * (f.shift.x + 0.0), f.scale.y * (f.shift.y + 1.0));
-                ffi::rlVertex3f(
-                    self.vertex[f.index[0]].point.x,
-                    self.vertex[f.index[0]].point.y,
-                    self.vertex[f.index[0]].point.z,
-                );
-                ffi::rlTexCoord2f(f.scale.x * (f.shift.x + 1.0), f.scale.y * (f.shift.y + 1.0));
-                ffi::rlVertex3f(
-                    self.vertex[f.index[1]].point.x,
-                    self.vertex[f.index[1]].point.y,
-                    self.vertex[f.index[1]].point.z,
-                );
-                ffi::rlTexCoord2f(f.scale.x * (f.shift.x + 1.0), f.scale.y * (f.shift.y + 0.0));
-                ffi::rlVertex3f(
-                    self.vertex[f.index[2]].point.x,
-                    self.vertex[f.index[2]].point.y,
-                    self.vertex[f.index[2]].point.z,
-                );
-                ffi::rlTexCoord2f(f.scale.x * (f.shift.x + 0.0), f.scale.y * (f.shift.y + 0.0));
-                ffi::rlVertex3f(
-                    self.vertex[f.index[3]].point.x,
-                    self.vertex[f.index[3]].point.y,
-                    self.vertex[f.index[3]].point.z,
-                );
+                let point = self.point(f);
+                let uv = f.uv(&point, asset.texture_shape(&f.texture));
+
+                // draw the face as a triangle fan.
+                for i in 1..point.len().saturating_sub(1) {
+                    for j in [0, i, i + 1] {
+                        ffi::rlTexCoord2f(uv[j].x, uv[j].y);
+                        ffi::rlVertex3f(point[j].x, point[j].y, point[j].z);
+                    }
+                }
             }
 
-            // end quad draw.
+            // end triangle draw.
             ffi::rlEnd();
 
             // clear texture.
@@ -631,35 +2035,32 @@ impl Brush {
 impl Default for Brush {
     #[rustfmt::skip]
     fn default() -> Self {
-        Self {
-            vertex: [
-                Vertex::new(-Self::DEFAULT_SHAPE, -Self::DEFAULT_SHAPE,  Self::DEFAULT_SHAPE),
-                Vertex::new( Self::DEFAULT_SHAPE, -Self::DEFAULT_SHAPE,  Self::DEFAULT_SHAPE),
-                Vertex::new( Self::DEFAULT_SHAPE,  Self::DEFAULT_SHAPE,  Self::DEFAULT_SHAPE),
-                Vertex::new(-Self::DEFAULT_SHAPE,  Self::DEFAULT_SHAPE,  Self::DEFAULT_SHAPE),
-                Vertex::new(-Self::DEFAULT_SHAPE, -Self::DEFAULT_SHAPE, -Self::DEFAULT_SHAPE),
-                Vertex::new( Self::DEFAULT_SHAPE, -Self::DEFAULT_SHAPE, -Self::DEFAULT_SHAPE),
-                Vertex::new( Self::DEFAULT_SHAPE,  Self::DEFAULT_SHAPE, -Self::DEFAULT_SHAPE),
-                Vertex::new(-Self::DEFAULT_SHAPE,  Self::DEFAULT_SHAPE, -Self::DEFAULT_SHAPE),
+        Self::new(
+            &[
+                Vector3::new(-Self::DEFAULT_SHAPE, -Self::DEFAULT_SHAPE,  Self::DEFAULT_SHAPE),
+                Vector3::new( Self::DEFAULT_SHAPE, -Self::DEFAULT_SHAPE,  Self::DEFAULT_SHAPE),
+                Vector3::new( Self::DEFAULT_SHAPE,  Self::DEFAULT_SHAPE,  Self::DEFAULT_SHAPE),
+                Vector3::new(-Self::DEFAULT_SHAPE,  Self::DEFAULT_SHAPE,  Self::DEFAULT_SHAPE),
+                Vector3::new(-Self::DEFAULT_SHAPE, -Self::DEFAULT_SHAPE, -Self::DEFAULT_SHAPE),
+                Vector3::new( Self::DEFAULT_SHAPE, -Self::DEFAULT_SHAPE, -Self::DEFAULT_SHAPE),
+                Vector3::new( Self::DEFAULT_SHAPE,  Self::DEFAULT_SHAPE, -Self::DEFAULT_SHAPE),
+                Vector3::new(-Self::DEFAULT_SHAPE,  Self::DEFAULT_SHAPE, -Self::DEFAULT_SHAPE),
             ],
-            face: Face::new_list(),
-            focus: false,
-        }
+            Face::new_list(),
+        )
     }
 }
 
 //================================================================
 
-#[derive(Deserialize, Serialize)]
+#[derive(Clone, PartialEq, Deserialize, Serialize)]
 pub struct Vertex {
-    pub focus: bool,
     pub point: Vector3,
 }
 
 impl Vertex {
     pub fn new(x: f32, y: f32, z: f32) -> Self {
         Self {
-            focus: false,
             point: Vector3::new(x, y, z),
         }
     }
@@ -667,48 +2068,380 @@ impl Vertex {
 
 //================================================================
 
-#[derive(Deserialize, Serialize)]
+// a convex polygon on a brush, in counter-clock-wise order as seen from outside.
+#[derive(Clone, PartialEq, Deserialize, Serialize)]
 pub struct Face {
-    pub focus: bool,
-    pub index: [usize; 4],
+    pub index: Vec<usize>,
+    #[serde(default)]
+    pub plane: Plane,
     pub shift: Vector2,
     pub scale: Vector2,
     pub color: Color,
     pub texture: Option<String>,
+    #[serde(default)]
+    pub projection: Projection,
+    // rotation of the texture, in degrees.
+    #[serde(default)]
+    pub angle: f32,
 }
 
 impl Face {
-    pub fn new(index: [usize; 4]) -> Self {
+    // texture pixel count per unit, with a scale of one. the same as a Quake unit, so that a texture keeps its size on export.
+    pub const TEXEL: f32 = Quake::SCALE;
+
+    pub fn new(index: Vec<usize>) -> Self {
         Self {
-            focus: false,
             index,
+            plane: Plane::default(),
             shift: Vector2::new(0.0, 0.0),
             scale: Vector2::new(1.0, 1.0),
             color: Color::WHITE,
             texture: None,
+            projection: Projection::default(),
+            angle: 0.0,
+        }
+    }
+
+    // get the texture coordinate of each corner of the face. "shape" is the size of the texture, in pixels.
+    pub fn uv(&self, point: &[Vector3], shape: Vector2) -> Vec<Vector2> {
+        self.uv_project(point, shape)
+            .iter()
+            .map(|uv| {
+                Vector2::new(
+                    self.scale.x * (self.shift.x + uv.x),
+                    self.scale.y * (self.shift.y + uv.y),
+                )
+            })
+            .collect()
+    }
+
+    // line the texture up with the edges of the face, keeping its angle. "shape" is the size of the texture, in pixels.
+    pub fn align(&mut self, point: &[Vector3], shape: Vector2, align: TextureAlign) {
+        let base = self.uv_project(point, shape);
+
+        let mut min = Vector2::new(f32::MAX, f32::MAX);
+        let mut max = Vector2::new(f32::MIN, f32::MIN);
+
+        for uv in &base {
+            min.x = min.x.min(uv.x);
+            min.y = min.y.min(uv.y);
+            max.x = max.x.max(uv.x);
+            max.y = max.y.max(uv.y);
+        }
+
+        if base.is_empty() {
+            return;
+        }
+
+        // a texture coordinate of zero is the left or top side of the texture, and of one the right or bottom side.
+        match align {
+            TextureAlign::Fit => {
+                self.scale = Vector2::new(
+                    1.0 / (max.x - min.x).max(f32::EPSILON),
+                    1.0 / (max.y - min.y).max(f32::EPSILON),
+                );
+                self.shift = Vector2::new(-min.x, -min.y);
+            }
+            TextureAlign::Center => {
+                self.shift.x = 0.5 / self.scale.x - (min.x + max.x) * 0.5;
+                self.shift.y = 0.5 / self.scale.y - (min.y + max.y) * 0.5;
+            }
+            TextureAlign::Left => self.shift.x = -min.x,
+            TextureAlign::Right => self.shift.x = 1.0 / self.scale.x - max.x,
+            TextureAlign::Top => self.shift.y = -min.y,
+            TextureAlign::Bottom => self.shift.y = 1.0 / self.scale.y - max.y,
+        }
+    }
+
+    // get the texture coordinate of each corner of the face, before shift and scale, but after rotation.
+    fn uv_project(&self, point: &[Vector3], shape: Vector2) -> Vec<Vector2> {
+        let base = match (self.projection, Plane::new_from_list(point)) {
+            (Projection::Fit, _) | (_, None) => Self::uv_base(point),
+            (projection, Some(plane)) => {
+                let (axis_u, axis_v) = projection.axis(plane.normal);
+                let shape = Vector2::new(shape.x.max(1.0), shape.y.max(1.0));
+
+                point
+                    .iter()
+                    .map(|p| {
+                        Vector2::new(
+                            p.dot(axis_u) * Self::TEXEL / shape.x,
+                            p.dot(axis_v) * Self::TEXEL / shape.y,
+                        )
+                    })
+                    .collect()
+            }
+        };
+
+        let (sin, cos) = (self.angle * DEG2RAD as f32).sin_cos();
+
+        base.iter()
+            .map(|uv| Vector2::new(uv.x * cos - uv.y * sin, uv.x * sin + uv.y * cos))
+            .collect()
+    }
+
+    // get the texture coordinate of each corner of a polygon, before shift and scale. the polygon is fit into a (0, 0) to (1, 1) rectangle,
+    // running along the first edge, so that the first corner of a rectangle is at (0, 1), the second at (1, 1), the third at (1, 0) and the last at (0, 0).
+    pub fn uv_base(point: &[Vector3]) -> Vec<Vector2> {
+        let Some((axis_x, axis_y, min, shape)) = Self::uv_rect(point) else {
+            return vec![Vector2::zero(); point.len()];
+        };
+
+        point
+            .iter()
+            .map(|p| {
+                Vector2::new(
+                    (p.dot(axis_x) - min.x) / shape.x,
+                    (p.dot(axis_y) - min.y) / shape.y,
+                )
+            })
+            .collect()
+    }
+
+    // get the rectangle a polygon is fit into for its texture coordinate: the axis along the first edge, the axis across it,
+    // and the corner and shape of the rectangle along them.
+    fn uv_rect(point: &[Vector3]) -> Option<(Vector3, Vector3, Vector2, Vector2)> {
+        let (Some(plane), Some(b)) = (Plane::new_from_list(point), point.get(1)) else {
+            return None;
+        };
+
+        let axis_x = (*b - point[0]).normalized();
+        let axis_y = axis_x.cross(plane.normal);
+
+        let mut min = Vector2::new(f32::MAX, f32::MAX);
+        let mut max = Vector2::new(f32::MIN, f32::MIN);
+
+        for p in point {
+            min.x = min.x.min(p.dot(axis_x));
+            min.y = min.y.min(p.dot(axis_y));
+            max.x = max.x.max(p.dot(axis_x));
+            max.y = max.y.max(p.dot(axis_y));
+        }
+
+        let shape = Vector2::new(
+            (max.x - min.x).max(f32::EPSILON),
+            (max.y - min.y).max(f32::EPSILON),
+        );
+
+        Some((axis_x, axis_y, min, shape))
+    }
+
+    // change the shift and scale of the face so that the texture keeps its size on the surface, and stays on the first corner,
+    // after the polygon has gone from "before" to "after". as the texture is fit along the first edge, it turns along with it.
+    // "shape" is the size of the texture, in pixels.
+    pub fn lock(&mut self, before: &[Vector3], after: &[Vector3], shape: Vector2) {
+        // a projected texture keeps its size on its own, it only has to be turned along with the first edge, and then shifted back
+        // onto the first corner.
+        if self.projection != Projection::Fit {
+            let Some(first) = self.uv_project(before, shape).first().copied() else {
+                return;
+            };
+
+            // the turn is measured without the shape of the texture, which would stretch one axis against the other.
+            let a = self.uv_project(before, Vector2::one());
+            let b = self.uv_project(after, Vector2::one());
+
+            if let (Some(a_edge), Some(b_edge)) =
+                (a.get(1).map(|uv| *uv - a[0]), b.get(1).map(|uv| *uv - b[0]))
+            {
+                let turn = (a_edge.y.atan2(a_edge.x) - b_edge.y.atan2(b_edge.x)) * RAD2DEG as f32;
+
+                self.angle += (turn + 180.0).rem_euclid(360.0) - 180.0;
+            }
+
+            if let Some(b) = self.uv_project(after, shape).first() {
+                self.shift += first - *b;
+            }
+
+            return;
+        }
+
+        if let (Some(a), Some(b), Some(a_first), Some(b_first)) = (
+            Self::uv_rect(before),
+            Self::uv_rect(after),
+            before.first(),
+            after.first(),
+        ) {
+            self.fit_keep(a, b, *a_first, *b_first);
+        }
+    }
+
+    // change the shift and scale of a fit texture so that it stays where it was on the surface, after the polygon has been cut
+    // from "before" to "after", on the same plane. the first edge of both should run the same way.
+    pub fn refit(&mut self, before: &[Vector3], after: &[Vector3]) {
+        if let (Some(a), Some(b), Some(first)) =
+            (Self::uv_rect(before), Self::uv_rect(after), after.first())
+        {
+            self.fit_keep(a, b, *first, *first);
+        }
+    }
+
+    // change the shift and scale of a fit texture, laid over the "a" rectangle before and over the "b" rectangle after, so
+    // that it keeps its texture coordinate per unit along both axes, and so that "b_point" gets the texture coordinate
+    // "a_point" had. both rectangles are as given by "uv_rect".
+    fn fit_keep(
+        &mut self,
+        (a_x, a_y, a_min, a_shape): (Vector3, Vector3, Vector2, Vector2),
+        (b_x, b_y, b_min, b_shape): (Vector3, Vector3, Vector2, Vector2),
+        a_point: Vector3,
+        b_point: Vector3,
+    ) {
+        // the texture is turned before it is shifted and scaled, as in "uv_project".
+        let (sin, cos) = (self.angle * DEG2RAD as f32).sin_cos();
+        let turn = |uv: Vector2| Vector2::new(uv.x * cos - uv.y * sin, uv.x * sin + uv.y * cos);
+
+        let a_base = turn(Vector2::new(
+            (a_point.dot(a_x) - a_min.x) / a_shape.x,
+            (a_point.dot(a_y) - a_min.y) / a_shape.y,
+        ));
+        let b_base = turn(Vector2::new(
+            (b_point.dot(b_x) - b_min.x) / b_shape.x,
+            (b_point.dot(b_y) - b_min.y) / b_shape.y,
+        ));
+        let origin = Vector2::new(
+            self.scale.x * (self.shift.x + a_base.x),
+            self.scale.y * (self.shift.y + a_base.y),
+        );
+
+        let scale = Vector2::new(
+            self.scale.x * b_shape.x / a_shape.x,
+            self.scale.y * b_shape.y / a_shape.y,
+        );
+
+        if scale.x.abs() <= f32::EPSILON || scale.y.abs() <= f32::EPSILON {
+            return;
         }
+
+        self.scale = scale;
+        self.shift = Vector2::new(origin.x / scale.x - b_base.x, origin.y / scale.y - b_base.y);
     }
 
-    pub fn new_list() -> [Self; 6] {
-        [
-            Face::new([0, 1, 2, 3]),
-            Face::new([5, 4, 7, 6]),
-            Face::new([3, 2, 6, 7]),
-            Face::new([1, 0, 4, 5]),
-            Face::new([1, 5, 6, 2]),
-            Face::new([4, 0, 3, 7]),
+    pub fn new_list() -> Vec<Self> {
+        vec![
+            Face::new(vec![0, 1, 2, 3]),
+            Face::new(vec![5, 4, 7, 6]),
+            Face::new(vec![3, 2, 6, 7]),
+            Face::new(vec![1, 0, 4, 5]),
+            Face::new(vec![1, 5, 6, 2]),
+            Face::new(vec![4, 0, 3, 7]),
         ]
     }
 }
 
 //================================================================
 
-#[derive(Deserialize, Serialize)]
+// the way the texture of a face is laid over it.
+#[derive(Copy, Clone, Default, PartialEq, Deserialize, Serialize)]
+pub enum Projection {
+    // stretch the texture over the face once, along its first edge.
+    #[default]
+    Fit,
+    // lay the texture flat over the face, keeping its size.
+    Face,
+    // lay the texture from the world axis closest to the normal of the face, as Quake does. faces on opposite sides are mirrored.
+    World,
+    // lay the texture from the side of a box around the face closest to its normal, so that no face is mirrored.
+    Box,
+}
+
+impl Projection {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Fit => "Fit",
+            Self::Face => "Face",
+            Self::World => "World",
+            Self::Box => "Box",
+        }
+    }
+
+    pub fn next(&self) -> Self {
+        match self {
+            Self::Fit => Self::Face,
+            Self::Face => Self::World,
+            Self::World => Self::Box,
+            Self::Box => Self::Fit,
+        }
+    }
+
+    // get the U and V texture axis for a face, given its normal. U runs to the right and V runs down, as seen from outside of the face.
+    pub fn axis(&self, normal: Vector3) -> (Vector3, Vector3) {
+        let axis = match self {
+            Self::Fit | Self::Face => normal,
+            Self::World | Self::Box => {
+                // prefer the floor, then the X wall, then the Z wall, as Quake does.
+                let a = Vector3::new(normal.x.abs(), normal.y.abs(), normal.z.abs());
+                let axis = if a.y >= a.x && a.y >= a.z {
+                    Vector3::new(0.0, normal.y.signum(), 0.0)
+                } else if a.x >= a.z {
+                    Vector3::new(normal.x.signum(), 0.0, 0.0)
+                } else {
+                    Vector3::new(0.0, 0.0, normal.z.signum())
+                };
+
+                if *self == Self::World {
+                    Vector3::new(axis.x.abs(), axis.y.abs(), axis.z.abs())
+                } else {
+                    axis
+                }
+            }
+        };
+
+        let up = Vector3::new(0.0, 1.0, 0.0);
+        let axis_u = if axis.dot(up).abs() > 0.999 {
+            Vector3::new(1.0, 0.0, 0.0)
+        } else {
+            up.cross(axis).normalized()
+        };
+
+        (axis_u, axis_u.cross(axis))
+    }
+}
+
+//================================================================
+
+// a way to line the texture of a face up with its edges.
+#[derive(Copy, Clone)]
+pub enum TextureAlign {
+    // stretch the texture over the face exactly once.
+    Fit,
+    // move the middle of the texture onto the middle of the face.
+    Center,
+    // move a side of the texture onto the same side of the face.
+    Left,
+    Right,
+    Top,
+    Bottom,
+}
+
+impl TextureAlign {
+    pub const LIST: [Self; 6] = [
+        Self::Fit,
+        Self::Center,
+        Self::Left,
+        Self::Right,
+        Self::Top,
+        Self::Bottom,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Fit => "Fit",
+            Self::Center => "Center",
+            Self::Left => "Justify Left",
+            Self::Right => "Justify Right",
+            Self::Top => "Justify Top",
+            Self::Bottom => "Justify Bottom",
+        }
+    }
+}
+
+//================================================================
+
+#[derive(Clone, PartialEq, Deserialize, Serialize)]
 pub struct Entity {
     pub position: Vector3,
     pub rotation: Vector3,
     pub scale: Vector3,
-    pub focus: bool,
     pub meta: EntityMeta,
 }
 
@@ -718,7 +2451,6 @@ impl Entity {
             position: Vector3::default(),
             rotation: Vector3::default(),
             scale: Vector3::one(),
-            focus: false,
             meta,
         }
     }
@@ -727,11 +2459,19 @@ impl Entity {
         self.position += value;
     }
 
-    pub fn rotation(&mut self, value: Vector3) {
+    // turn the entity, by an angle in degrees on every axis, moving it around a point.
+    pub fn rotation(&mut self, value: Vector3, pivot: Vector3) {
+        let matrix = Matrix::rotate_xyz(value * DEG2RAD as f32);
+
+        self.position = pivot + (self.position - pivot).transform_with(matrix);
         self.rotation += value;
     }
 
-    pub fn scale(&mut self, value: Vector3) {
+    // grow the scale of the entity, moving it away from a point by as much.
+    pub fn scale(&mut self, value: Vector3, pivot: Vector3) {
+        let matrix = Matrix::scale(1.0 + value.x, 1.0 + value.y, 1.0 + value.z);
+
+        self.position = pivot + (self.position - pivot).transform_with(matrix);
         self.scale += value;
     }
 
@@ -742,28 +2482,29 @@ impl Entity {
         )
     }
 
-    pub fn draw_3d(&self, lua: &Lua, draw: &mut RaylibMode3D<RaylibTextureMode<RaylibDrawHandle>>) {
+    pub fn draw_3d(
+        &self,
+        lua: &Lua,
+        draw: &mut RaylibMode3D<RaylibTextureMode<RaylibDrawHandle>>,
+        focus: bool,
+    ) -> Result<(), String> {
         draw.draw_bounding_box(
             self.bound_box(),
-            if self.focus { Color::GREEN } else { Color::RED },
+            if focus { Color::GREEN } else { Color::RED },
         );
 
-        let data = lua
-            .to_value(&self)
-            .map_err(|e| panic(&e.to_string()))
-            .unwrap();
+        let data = lua.to_value(&self).map_err(|e| e.to_string())?;
 
         if let Some(call) = &self.meta.call {
             let g = lua.globals();
             let g = g
                 .get::<mlua::Function>(&**call)
-                .map_err(|e| panic(&e.to_string()))
-                .unwrap();
+                .map_err(|e| e.to_string())?;
 
-            g.call::<()>(data)
-                .map_err(|e| panic(&e.to_string()))
-                .unwrap();
+            g.call::<()>(data).map_err(|e| e.to_string())?;
         }
+
+        Ok(())
     }
 
     pub fn draw_2d(
@@ -772,6 +2513,7 @@ impl Entity {
         asset: &Asset,
         view: &Camera3D,
         port: Vector2,
+        focus: bool,
     ) {
         let text = draw.get_world_to_screen_ex(
             self.position + Vector3::new(0.0, self.meta.shape.max.y + 1.0, 0.0),
@@ -790,7 +2532,7 @@ impl Entity {
             ),
             0.25,
             4,
-            Color::new(0, 0, 0, if self.focus { 255 } else { 127 }),
+            Color::new(0, 0, 0, if focus { 255 } else { 127 }),
         );
 
         draw.draw_text_ex(
@@ -804,7 +2546,7 @@ impl Entity {
     }
 }
 
-#[derive(Clone, Deserialize, Serialize)]
+#[derive(Clone, PartialEq, Deserialize, Serialize)]
 pub struct EntityMeta {
     pub name: String,
     pub info: String,
@@ -813,7 +2555,7 @@ pub struct EntityMeta {
     pub call: Option<String>,
 }
 
-#[derive(Clone, Deserialize, Serialize)]
+#[derive(Clone, PartialEq, Deserialize, Serialize)]
 pub struct EntityData {
     pub info: String,
     pub kind: serde_json::Value,
@@ -821,7 +2563,7 @@ pub struct EntityData {
 
 //================================================================
 
-#[derive(Default)]
+#[derive(Copy, Clone, Default, PartialEq)]
 pub enum Widget {
     #[default]
     Position,
@@ -830,6 +2572,209 @@ pub enum Widget {
     Vertex,
     Edge,
     Face,
+    Clip,
+}
+
+//================================================================
+
+// the state of the clip tool.
+#[derive(Default)]
+pub struct Clip {
+    // every point picked so far, alongside the view they were picked in.
+    pub point: Vec<Vector3>,
+    pub view: Option<usize>,
+    // the direction the view was looking at, for a two-point cut.
+    pub look: Vector3,
+    pub side: ClipSide,
+    // the outline of whatever the cut would remove, alongside the history revision, selection, points, look and side it was
+    // worked out from.
+    preview: Vec<Brush>,
+    preview_from: Option<(usize, Selection, Vec<Vector3>, Vector3, ClipSide)>,
+}
+
+impl Clip {
+    // pick a new point. an orthographic view takes two points, and the cut runs along the view direction.
+    // the perspective view takes three points, picked on the surface of any brush.
+    pub fn pick(&mut self, world: &World, user: &User, ray: Ray, camera: &Camera3D, view: usize) {
+        let point = match camera.camera_type() {
+            CameraProjection::CAMERA_PERSPECTIVE => {
+                let mut hit: Option<RayCollision> = None;
+
+                for brush in &world.brush {
+                    for face in &brush.face {
+                        let point = brush.point(face);
+
+                        for j in 1..point.len().saturating_sub(1) {
+                            let ray =
+                                get_ray_collision_triangle(ray, point[0], point[j], point[j + 1]);
+
+                            if ray.hit && hit.is_none_or(|hit| ray.distance < hit.distance) {
+                                hit = Some(ray);
+                            }
+                        }
+                    }
+                }
+
+                match hit {
+                    Some(hit) => hit.point,
+                    None => return,
+                }
+            }
+            CameraProjection::CAMERA_ORTHOGRAPHIC => {
+                let look = (camera.target - camera.position).normalized();
+                self.look = look;
+
+                // flatten the point onto the plane of the camera target, then snap it to the grid.
+                user.snap_point(ray.position - look * look.dot(ray.position - camera.target))
+            }
+        };
+
+        // a point from a different view, or a point past the last one, begins a new cut.
+        if self.view != Some(view) || self.point.len() >= self.count(camera) {
+            self.point.clear();
+        }
+
+        self.view = Some(view);
+        self.point.push(point);
+    }
+
+    // get the number of points a cut takes in a view.
+    pub fn count(&self, camera: &Camera3D) -> usize {
+        match camera.camera_type() {
+            CameraProjection::CAMERA_PERSPECTIVE => 3,
+            CameraProjection::CAMERA_ORTHOGRAPHIC => 2,
+        }
+    }
+
+    // get the plane of the cut, if enough points have been picked.
+    pub fn plane(&self) -> Option<Plane> {
+        match self.point.as_slice() {
+            [a, b] => {
+                let normal = (*b - *a).cross(self.look);
+
+                if normal.length() <= f32::EPSILON {
+                    return None;
+                }
+
+                let normal = normal.normalized();
+
+                Some(Plane::new(normal, normal.dot(*a)))
+            }
+            [a, b, c] => Plane::new_from_point(*a, *b, *c),
+            _ => None,
+        }
+    }
+
+    // split a brush along the cut, keeping the side (or sides) that has been picked.
+    pub fn split(&self, brush: &Brush) -> Option<Vec<Brush>> {
+        let plane = self.plane()?;
+
+        // the cap face copies the face closest to the cut, preferring a face the cut does not run through.
+        let face = brush
+            .face
+            .iter()
+            .map(|face| {
+                let point = brush.point(face);
+                let distance: Vec<f32> = point.iter().map(|p| plane.distance_to(*p)).collect();
+                let cross = distance.iter().any(|d| *d > Plane::EPSILON)
+                    && distance.iter().any(|d| *d < -Plane::EPSILON);
+                let near = distance.iter().map(|d| d.abs()).fold(f32::MAX, f32::min);
+
+                (face, (cross, near))
+            })
+            .min_by(|a, b| a.1 .0.cmp(&b.1 .0).then(a.1 .1.total_cmp(&b.1 .1)))?
+            .0;
+
+        let (back, front) = brush.split(plane, face);
+
+        let list = match self.side {
+            ClipSide::Both => vec![back, front],
+            ClipSide::Front => vec![front],
+            ClipSide::Back => vec![back],
+        };
+
+        Some(list.into_iter().flatten().collect())
+    }
+
+    // work out the outline of whatever the cut would remove again, if the world, the selection or the cut has changed since.
+    pub fn update(&mut self, world: &World, history: &History) {
+        let from = (
+            history.revision(),
+            world.selection.clone(),
+            self.point.clone(),
+            self.look,
+            self.side,
+        );
+
+        if self.preview_from.as_ref() == Some(&from) {
+            return;
+        }
+
+        self.preview.clear();
+        self.preview_from = Some(from);
+
+        let Some(plane) = self.plane() else {
+            return;
+        };
+
+        for (i, brush) in world.brush.iter().enumerate() {
+            if !world.selection.brush(i) {
+                continue;
+            }
+
+            let (back, front) = brush.split(plane, &Face::new(Vec::new()));
+
+            let cut = match self.side {
+                ClipSide::Both => None,
+                ClipSide::Front => back,
+                ClipSide::Back => front,
+            };
+
+            self.preview.extend(cut);
+        }
+    }
+
+    // draw every point, the cut, and the outline of whatever would be removed, as of the last update.
+    pub fn draw(&self, draw: &mut RaylibMode3D<RaylibTextureMode<RaylibDrawHandle>>) {
+        for point in &self.point {
+            draw.draw_cube(*point, 0.25, 0.25, 0.25, Color::ORANGE);
+        }
+
+        for pair in self.point.windows(2) {
+            draw.draw_line_3D(pair[0], pair[1], Color::ORANGE);
+        }
+
+        for cut in &self.preview {
+            cut.draw_wire(draw, Color::RED);
+        }
+    }
+}
+
+// which side of the cut to keep: the side the plane is facing, the other one, or both.
+#[derive(Copy, Clone, Default, PartialEq)]
+pub enum ClipSide {
+    #[default]
+    Both,
+    Front,
+    Back,
+}
+
+impl ClipSide {
+    pub fn next(&self) -> Self {
+        match self {
+            Self::Both => Self::Front,
+            Self::Front => Self::Back,
+            Self::Back => Self::Both,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Both => "Both",
+            Self::Front => "Front",
+            Self::Back => "Back",
+        }
+    }
 }
 
 //================================================================
@@ -846,6 +2791,16 @@ impl Asset {
             outer: Outer::default(),
         }
     }
+
+    // get the size of a texture, in pixels, or of the default texture if there is none.
+    pub fn texture_shape(&self, texture: &Option<String>) -> Vector2 {
+        let texture = texture
+            .as_ref()
+            .and_then(|texture| self.outer.texture.get(texture))
+            .unwrap_or(&self.inner.default);
+
+        Vector2::new(texture.width as f32, texture.height as f32)
+    }
 }
 
 pub struct Inner {
@@ -862,6 +2817,7 @@ pub struct Inner {
     pub vertex: Texture2D,
     pub edge: Texture2D,
     pub face: Texture2D,
+    pub clip: Texture2D,
     pub user: Texture2D,
     pub reload: Texture2D,
     pub import: Texture2D,
@@ -874,7 +2830,7 @@ impl Inner {
     pub const ICON: &'static [u8] = include_bytes!("asset/icon.png");
     const FONT:     &'static [u8] = include_bytes!("asset/font.ttf");
     const LOGO:     &'static [u8] = include_bytes!("asset/logo.png");
-    const DEFAULT:  &'static [u8] = include_bytes!("asset/default.png");
+    pub const DEFAULT: &'static [u8] = include_bytes!("asset/default.png");
     const DROP_A:   &'static [u8] = include_bytes!("asset/drop-a.png");
     const DROP_B:   &'static [u8] = include_bytes!("asset/drop-b.png");
     const TEXTURE:  &'static [u8] = include_bytes!("asset/texture.png");
@@ -885,6 +2841,7 @@ impl Inner {
     const VERTEX:   &'static [u8] = include_bytes!("asset/vertex.png");
     const EDGE:     &'static [u8] = include_bytes!("asset/edge.png");
     const FACE:     &'static [u8] = include_bytes!("asset/face.png");
+    const CLIP:     &'static [u8] = include_bytes!("asset/clip.png");
     const USER:     &'static [u8] = include_bytes!("asset/user.png");
     const RELOAD:   &'static [u8] = include_bytes!("asset/reload.png");
     const IMPORT:   &'static [u8] = include_bytes!("asset/import.png");
@@ -906,6 +2863,7 @@ impl Inner {
             vertex:   load_texture(handle, thread, Self::VERTEX),
             edge:     load_texture(handle, thread, Self::EDGE),
             face:     load_texture(handle, thread, Self::FACE),
+            clip:     load_texture(handle, thread, Self::CLIP),
             user:     load_texture(handle, thread, Self::USER),
             reload:   load_texture(handle, thread, Self::RELOAD),
             import:   load_texture(handle, thread, Self::IMPORT),
@@ -922,13 +2880,19 @@ pub struct Outer {
 
 impl Outer {
     // load a texture from disk into the hash-map.
-    pub fn set_texture(&mut self, handle: &mut RaylibHandle, thread: &RaylibThread, path: &str) {
+    pub fn set_texture(
+        &mut self,
+        handle: &mut RaylibHandle,
+        thread: &RaylibThread,
+        path: &str,
+    ) -> Result<(), String> {
         let texture = handle
             .load_texture(thread, path)
-            .map_err(|e| panic(&e.to_string()))
-            .unwrap();
+            .map_err(|e| format!("Outer::set_texture(): {e}"))?;
 
         self.texture.insert(path.to_string(), texture);
+
+        Ok(())
     }
 
     // load a texture from disk into the hash-map, using a path list instead.
@@ -937,10 +2901,12 @@ impl Outer {
         handle: &mut RaylibHandle,
         thread: &RaylibThread,
         path: &[String],
-    ) {
+    ) -> Result<(), String> {
         for p in path {
-            self.set_texture(handle, thread, p);
+            self.set_texture(handle, thread, p)?;
         }
+
+        Ok(())
     }
 }
 
@@ -984,6 +2950,7 @@ impl View {
 
 // a representation of user configuration data.
 #[derive(Deserialize, Serialize)]
+#[serde(default)]
 pub struct User {
     pub mouse_speed: [f32; 2],
     pub move_x_a: Input,
@@ -1000,15 +2967,80 @@ pub struct User {
     pub vertex: Input,
     pub edge: Input,
     pub face: Input,
+    pub clip: Input,
+    pub clip_apply: Input,
+    pub clip_side: Input,
+    pub normal: Input,
     pub user: Input,
     pub reload: Input,
     pub import: Input,
     pub export: Input,
     pub exit: Input,
+    // an input without a modifier ignores every modifier, so this must not share its key with one, such as a move key.
+    pub save: Input,
+    pub undo: Input,
+    pub redo: Input,
+    pub carve: Input,
+    pub hollow: Input,
+    pub merge: Input,
+    pub extrude: Input,
+    pub grow: Input,
+    pub inset: Input,
+    pub lock: Input,
+    pub grid_up: Input,
+    pub grid_down: Input,
+    // toggle snapping to the grid, to the angle step and to the scale step, each on its own.
+    pub snap: Input,
+    pub snap_angle: Input,
+    pub snap_step: Input,
+    pub select_add: Input,
+    pub select_subtract: Input,
+    pub select_all: Input,
+    pub select_none: Input,
+    pub select_invert: Input,
+    // maximum number of steps in the undo history.
+    pub history: usize,
+    // delay between every autosave, in seconds. zero will disable autosaving.
+    pub autosave: f32,
+    // maximum number of autosave snapshots to keep.
+    pub autosave_count: usize,
+    // wall thickness of the hollow operation, snapped to the grid.
+    pub hollow_thickness: f32,
+    // texture of every inner face made by the hollow operation, as given to "mallet.map_texture". none will use the default texture.
+    pub hollow_texture: Option<String>,
+    // maximum volume the merge operation may add, as a fraction of the volume of the merged brush.
+    pub merge_tolerance: f32,
+    // if true, moving, turning or scaling a brush keeps the texture of every face where it was on the surface, rather than fitting it to the face again.
+    pub texture_lock: bool,
+    // a brush thinner than this is reported as a micro-brush by the validity check.
+    pub micro_brush: f32,
+    // distance of the extrude and grow operations, snapped to the grid.
+    pub extrude_distance: f32,
+    // distance every edge of a face moves inward by with the inset operation.
+    pub inset_distance: f32,
+    // distance the middle face is raised by with the inset operation. it must be above zero.
+    pub inset_depth: f32,
+    // size of the grid, as a power of two. it is also the step of every move made with the keyboard.
+    pub grid: f32,
+    // if true, every move, and every length of a new brush, is snapped to the grid.
+    pub snap_position: bool,
+    // if true, every turn is snapped to the angle step.
+    pub snap_rotation: bool,
+    // if true, every scale is snapped to the scale step.
+    pub snap_scale: bool,
+    // increment of every turn, in degrees. it is also the step of every turn made with the keyboard.
+    pub angle_step: f32,
+    // increment of every scale. it is also the step of every scale made with the keyboard.
+    pub scale_step: f32,
 }
 
 impl User {
     pub const FILE_NAME: &'static str = "user.json";
+    // smallest and largest size of the grid.
+    pub const GRID_MIN: f32 = 0.125;
+    pub const GRID_MAX: f32 = 64.0;
+    // smallest scale a brush or entity may be scaled down to.
+    pub const SCALE_MIN: f32 = 0.01;
 
     // create a new user, using existing user data from disk, or the default data.
     pub fn new() -> Self {
@@ -1028,6 +3060,49 @@ impl User {
             Self::default()
         }
     }
+
+    // double the size of the grid, or halve it, keeping it a power of two.
+    pub fn set_grid(&mut self, up: bool) {
+        let power = self.grid.max(Self::GRID_MIN).log2().round() + if up { 1.0 } else { -1.0 };
+
+        self.grid = power.exp2().clamp(Self::GRID_MIN, Self::GRID_MAX);
+    }
+
+    // snap a point to the grid, if snapping is on.
+    pub fn snap_point(&self, point: Vector3) -> Vector3 {
+        if self.snap_position {
+            snap(&point, self.grid)
+        } else {
+            point
+        }
+    }
+
+    // snap a length to the grid, if snapping is on, never going below a single step of it.
+    pub fn snap_length(&self, length: f32) -> f32 {
+        if self.snap_position {
+            ((length / self.grid).round() * self.grid).max(self.grid)
+        } else {
+            length.max(Self::GRID_MIN)
+        }
+    }
+
+    // snap an angle, in degrees, to the angle step, if snapping is on.
+    pub fn snap_angle(&self, angle: f32) -> f32 {
+        if self.snap_rotation && self.angle_step > 0.0 {
+            (angle / self.angle_step).round() * self.angle_step
+        } else {
+            angle
+        }
+    }
+
+    // snap a scale to the scale step, if snapping is on, never going below a single step of it.
+    pub fn snap_scale(&self, scale: f32) -> f32 {
+        if self.snap_scale && self.scale_step > 0.0 {
+            ((scale / self.scale_step).round() * self.scale_step).max(self.scale_step)
+        } else {
+            scale.max(Self::SCALE_MIN)
+        }
+    }
 }
 
 #[rustfmt::skip]
@@ -1049,11 +3124,52 @@ impl Default for User {
             vertex:   Input::new(None, Key::Keyboard(KEY_FOUR)),
             edge:     Input::new(None, Key::Keyboard(KEY_FIVE)),
             face:     Input::new(None, Key::Keyboard(KEY_SIX)),
+            clip:     Input::new(None, Key::Keyboard(KEY_NINE)),
+            clip_apply: Input::new(None, Key::Keyboard(KEY_ENTER)),
+            clip_side:  Input::new(None, Key::Keyboard(KEY_TAB)),
+            normal:   Input::new(None, Key::Keyboard(KEY_LEFT_ALT)),
             user:     Input::new(Some(Key::Keyboard(KEY_LEFT_CONTROL)), Key::Keyboard(KEY_Z)),
             reload:   Input::new(Some(Key::Keyboard(KEY_LEFT_CONTROL)), Key::Keyboard(KEY_X)),
             import:   Input::new(Some(Key::Keyboard(KEY_LEFT_CONTROL)), Key::Keyboard(KEY_C)),
             export:   Input::new(Some(Key::Keyboard(KEY_LEFT_CONTROL)), Key::Keyboard(KEY_V)),
             exit:     Input::new(Some(Key::Keyboard(KEY_LEFT_CONTROL)), Key::Keyboard(KEY_B)),
+            save:     Input::new(Some(Key::Keyboard(KEY_LEFT_CONTROL)), Key::Keyboard(KEY_F)),
+            undo:     Input::new(Some(Key::Keyboard(KEY_LEFT_CONTROL)), Key::Keyboard(KEY_U)),
+            redo:     Input::new(Some(Key::Keyboard(KEY_LEFT_CONTROL)), Key::Keyboard(KEY_Y)),
+            carve:    Input::new(Some(Key::Keyboard(KEY_LEFT_CONTROL)), Key::Keyboard(KEY_K)),
+            hollow:   Input::new(Some(Key::Keyboard(KEY_LEFT_CONTROL)), Key::Keyboard(KEY_H)),
+            merge:    Input::new(Some(Key::Keyboard(KEY_LEFT_CONTROL)), Key::Keyboard(KEY_M)),
+            extrude:  Input::new(Some(Key::Keyboard(KEY_LEFT_CONTROL)), Key::Keyboard(KEY_E)),
+            grow:     Input::new(Some(Key::Keyboard(KEY_LEFT_CONTROL)), Key::Keyboard(KEY_G)),
+            inset:    Input::new(Some(Key::Keyboard(KEY_LEFT_CONTROL)), Key::Keyboard(KEY_I)),
+            lock:     Input::new(Some(Key::Keyboard(KEY_LEFT_CONTROL)), Key::Keyboard(KEY_T)),
+            grid_up:   Input::new(None, Key::Keyboard(KEY_RIGHT_BRACKET)),
+            grid_down: Input::new(None, Key::Keyboard(KEY_LEFT_BRACKET)),
+            snap:       Input::new(Some(Key::Keyboard(KEY_LEFT_CONTROL)), Key::Keyboard(KEY_P)),
+            snap_angle: Input::new(Some(Key::Keyboard(KEY_LEFT_CONTROL)), Key::Keyboard(KEY_O)),
+            snap_step:  Input::new(Some(Key::Keyboard(KEY_LEFT_CONTROL)), Key::Keyboard(KEY_J)),
+            select_add:      Input::new(None, Key::Keyboard(KEY_LEFT_SHIFT)),
+            select_subtract: Input::new(None, Key::Keyboard(KEY_LEFT_CONTROL)),
+            select_all:      Input::new(Some(Key::Keyboard(KEY_LEFT_CONTROL)), Key::Keyboard(KEY_L)),
+            select_none:     Input::new(Some(Key::Keyboard(KEY_LEFT_CONTROL)), Key::Keyboard(KEY_N)),
+            select_invert:   Input::new(Some(Key::Keyboard(KEY_LEFT_CONTROL)), Key::Keyboard(KEY_R)),
+            history:  64,
+            autosave: 60.0,
+            autosave_count: 8,
+            hollow_thickness: 1.0,
+            hollow_texture: None,
+            merge_tolerance: 0.001,
+            texture_lock: false,
+            micro_brush: 0.125,
+            extrude_distance: 1.0,
+            inset_distance: 0.25,
+            inset_depth: 0.25,
+            grid: 1.0,
+            snap_position: true,
+            snap_rotation: true,
+            snap_scale: true,
+            angle_step: 15.0,
+            scale_step: 0.125,
         }
     }
 }
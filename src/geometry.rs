@@ -0,0 +1,252 @@
+/*
+* BSD Zero Clause License
+*
+* Copyright (c) 2025 sockentrocken
+*
+* Permission to use, copy, modify, and/or distribute this software for any
+* purpose with or without fee is hereby granted.
+*
+* THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHOR DISCLAIMS ALL WARRANTIES WITH
+* REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF MERCHANTABILITY
+* AND FITNESS. IN NO EVENT SHALL THE AUTHOR BE LIABLE FOR ANY SPECIAL, DIRECT,
+* INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES WHATSOEVER RESULTING FROM
+* LOSS OF USE, DATA OR PROFITS, WHETHER IN AN ACTION OF CONTRACT, NEGLIGENCE OR
+* OTHER TORTIOUS ACTION, ARISING OUT OF OR IN CONNECTION WITH THE USE OR
+* PERFORMANCE OF THIS SOFTWARE.
+*/
+
+use raylib::prelude::*;
+use serde::{Deserialize, Serialize};
+
+//================================================================
+
+// a plane, where every point for which dot(normal, point) == distance lies on the plane.
+// the side the normal points to is the front (outside), the other side is the back (inside).
+#[derive(Copy, Clone, Debug, Default, PartialEq, Deserialize, Serialize)]
+pub struct Plane {
+    pub normal: Vector3,
+    pub distance: f32,
+}
+
+impl Plane {
+    pub const EPSILON: f32 = 0.001;
+
+    pub fn new(normal: Vector3, distance: f32) -> Self {
+        Self { normal, distance }
+    }
+
+    // create a plane from three points, in counter-clock-wise order as seen from the front.
+    pub fn new_from_point(a: Vector3, b: Vector3, c: Vector3) -> Option<Self> {
+        let normal = (b - a).cross(c - a);
+        let length = normal.length();
+
+        if length <= f32::EPSILON {
+            return None;
+        }
+
+        let normal = normal / length;
+
+        Some(Self::new(normal, normal.dot(a)))
+    }
+
+    // create a plane from a polygon, in counter-clock-wise order as seen from the front. the polygon does not need to be perfectly flat.
+    pub fn new_from_list(point: &[Vector3]) -> Option<Self> {
+        if point.len() < 3 {
+            return None;
+        }
+
+        let mut normal = Vector3::zero();
+        let mut center = Vector3::zero();
+
+        // Newell's method.
+        for (i, a) in point.iter().enumerate() {
+            let b = point[(i + 1) % point.len()];
+
+            normal.x += (a.y - b.y) * (a.z + b.z);
+            normal.y += (a.z - b.z) * (a.x + b.x);
+            normal.z += (a.x - b.x) * (a.y + b.y);
+            center += *a;
+        }
+
+        let length = normal.length();
+
+        if length <= f32::EPSILON {
+            return None;
+        }
+
+        let normal = normal / length;
+        let center = center / point.len() as f32;
+
+        Some(Self::new(normal, normal.dot(center)))
+    }
+
+    // get the same plane, facing the other way.
+    pub fn flip(&self) -> Self {
+        Self::new(-self.normal, -self.distance)
+    }
+
+    // signed distance from the plane to a point. positive is in front, negative is behind.
+    pub fn distance_to(&self, point: Vector3) -> f32 {
+        self.normal.dot(point) - self.distance
+    }
+
+    // check if two planes are the same, within the epsilon.
+    pub fn equal(&self, other: &Plane) -> bool {
+        (self.normal - other.normal).length() <= Self::EPSILON
+            && (self.distance - other.distance).abs() <= Self::EPSILON
+    }
+
+    // get the point where three planes meet, if there is one.
+    pub fn intersect(a: &Plane, b: &Plane, c: &Plane) -> Option<Vector3> {
+        let cross_bc = b.normal.cross(c.normal);
+        let det = a.normal.dot(cross_bc);
+
+        if det.abs() <= f32::EPSILON {
+            return None;
+        }
+
+        let point = (cross_bc * a.distance
+            + c.normal.cross(a.normal) * b.distance
+            + a.normal.cross(b.normal) * c.distance)
+            / det;
+
+        Some(point)
+    }
+}
+
+//================================================================
+
+// a convex polyhedron, made out of a vertex list and a face list.
+// each face is a list of vertex indices, in counter-clock-wise order as seen from outside, alongside the plane it was built from.
+pub struct Polyhedron {
+    pub vertex: Vec<Vector3>,
+    pub face: Vec<(usize, Vec<usize>)>,
+}
+
+impl Polyhedron {
+    // build the polyhedron enclosed by the back side of every plane. returns None if the planes do not enclose a volume.
+    pub fn new_from_plane(plane: &[Plane]) -> Option<Self> {
+        let mut vertex: Vec<Vector3> = Vec::new();
+
+        // every corner is where three planes meet, as long as it is not in front of any other plane.
+        for i in 0..plane.len() {
+            for j in (i + 1)..plane.len() {
+                for k in (j + 1)..plane.len() {
+                    if let Some(point) = Plane::intersect(&plane[i], &plane[j], &plane[k]) {
+                        if plane.iter().all(|p| p.distance_to(point) <= Plane::EPSILON)
+                            && !vertex
+                                .iter()
+                                .any(|v| (*v - point).length() <= Plane::EPSILON)
+                        {
+                            vertex.push(point);
+                        }
+                    }
+                }
+            }
+        }
+
+        let mut face = Vec::new();
+
+        for (i, p) in plane.iter().enumerate() {
+            // skip duplicated planes.
+            if plane[..i].iter().any(|other| other.equal(p)) {
+                continue;
+            }
+
+            let index: Vec<usize> = (0..vertex.len())
+                .filter(|v| p.distance_to(vertex[*v]).abs() <= Plane::EPSILON)
+                .collect();
+
+            if index.len() < 3 {
+                continue;
+            }
+
+            face.push((i, Self::sort_face(&vertex, index, p.normal)));
+        }
+
+        if face.len() < 4 {
+            return None;
+        }
+
+        Some(Self { vertex, face })
+    }
+
+    // get the plane of every face of the convex hull of a point list.
+    pub fn hull_plane(point: &[Vector3]) -> Vec<Plane> {
+        let mut list: Vec<Plane> = Vec::new();
+
+        // every plane through three points, with every other point behind it, is a face of the hull.
+        for i in 0..point.len() {
+            for j in (i + 1)..point.len() {
+                for k in (j + 1)..point.len() {
+                    let Some(plane) = Plane::new_from_point(point[i], point[j], point[k]) else {
+                        continue;
+                    };
+
+                    let plane = {
+                        if point
+                            .iter()
+                            .all(|p| plane.distance_to(*p) <= Plane::EPSILON)
+                        {
+                            plane
+                        } else if point
+                            .iter()
+                            .all(|p| plane.distance_to(*p) >= -Plane::EPSILON)
+                        {
+                            plane.flip()
+                        } else {
+                            continue;
+                        }
+                    };
+
+                    if !list.iter().any(|p| p.equal(&plane)) {
+                        list.push(plane);
+                    }
+                }
+            }
+        }
+
+        list
+    }
+
+    // get the volume enclosed by a list of faces, in counter-clock-wise order as seen from outside.
+    pub fn volume_list<'a>(vertex: &[Vector3], face: impl Iterator<Item = &'a [usize]>) -> f32 {
+        let mut volume = 0.0;
+
+        // sum up the signed volume of every tetrahedron between the origin and a triangle of a face.
+        for index in face {
+            for i in 1..index.len().saturating_sub(1) {
+                let a = vertex[index[0]];
+                let b = vertex[index[i]];
+                let c = vertex[index[i + 1]];
+
+                volume += a.dot(b.cross(c)) / 6.0;
+            }
+        }
+
+        volume
+    }
+
+    // sort the vertex indices of a face in counter-clock-wise order around the normal.
+    pub fn sort_face(vertex: &[Vector3], mut index: Vec<usize>, normal: Vector3) -> Vec<usize> {
+        let mut center = Vector3::zero();
+
+        for i in &index {
+            center += vertex[*i];
+        }
+
+        center /= index.len() as f32;
+
+        let axis_x = (vertex[index[0]] - center).normalized();
+        let axis_y = normal.cross(axis_x);
+
+        let angle = |i: &usize| {
+            let point = vertex[*i] - center;
+            point.dot(axis_y).atan2(point.dot(axis_x))
+        };
+
+        index.sort_by(|a, b| angle(a).total_cmp(&angle(b)));
+
+        index
+    }
+}
@@ -0,0 +1,366 @@
+/*
+* BSD Zero Clause License
+*
+* Copyright (c) 2025 sockentrocken
+*
+* Permission to use, copy, modify, and/or distribute this software for any
+* purpose with or without fee is hereby granted.
+*
+* THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHOR DISCLAIMS ALL WARRANTIES WITH
+* REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF MERCHANTABILITY
+* AND FITNESS. IN NO EVENT SHALL THE AUTHOR BE LIABLE FOR ANY SPECIAL, DIRECT,
+* INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES WHATSOEVER RESULTING FROM
+* LOSS OF USE, DATA OR PROFITS, WHETHER IN AN ACTION OF CONTRACT, NEGLIGENCE OR
+* OTHER TORTIOUS ACTION, ARISING OUT OF OR IN CONNECTION WITH THE USE OR
+* PERFORMANCE OF THIS SOFTWARE.
+*/
+
+use crate::editor::*;
+
+//================================================================
+
+use raylib::prelude::*;
+use serde_json::{json, Value};
+use std::ffi::CString;
+
+//================================================================
+
+// a binary glTF 2.0 model. every brush is a mesh node with a primitive per texture, and every entity is an empty node.
+pub struct Glb {
+    pub json: Value,
+    pub data: Vec<u8>,
+}
+
+impl Glb {
+    pub const FILE_KIND: &'static str = "glb";
+    pub const FILE_INFO: &'static str = "glTF 2.0 Binary";
+
+    // GL constants used by the format.
+    const KIND_FLOAT: u32 = 5126;
+    const KIND_INDEX: u32 = 5125;
+    const TARGET_ARRAY: u32 = 34962;
+    const TARGET_INDEX: u32 = 34963;
+
+    pub fn new_from_world(world: &World, asset: &Asset) -> Self {
+        let mut result = Self {
+            json: Value::Null,
+            data: Vec::new(),
+        };
+
+        let mut texture: Vec<Option<String>> = Vec::new();
+        let mut buffer_view = Vec::new();
+        let mut accessor = Vec::new();
+        let mut mesh = Vec::new();
+        let mut node = Vec::new();
+
+        for (i, brush) in world.brush.iter().enumerate() {
+            let mut primitive = Vec::new();
+            let mut group: Vec<(usize, GlbPrimitive)> = Vec::new();
+
+            // group every face by texture.
+            for face in &brush.face {
+                let material = match texture.iter().position(|t| *t == face.texture) {
+                    Some(index) => index,
+                    None => {
+                        texture.push(face.texture.clone());
+                        texture.len() - 1
+                    }
+                };
+
+                let index = match group.iter().position(|(m, _)| *m == material) {
+                    Some(index) => index,
+                    None => {
+                        group.push((material, GlbPrimitive::default()));
+                        group.len() - 1
+                    }
+                };
+
+                group[index]
+                    .1
+                    .push(brush, face, asset.texture_shape(&face.texture));
+            }
+
+            for (material, data) in group {
+                primitive.push(json!({
+                    "attributes": {
+                        "POSITION":   result.push_accessor(&mut buffer_view, &mut accessor, &data.position, 3, true),
+                        "NORMAL":     result.push_accessor(&mut buffer_view, &mut accessor, &data.normal,   3, false),
+                        "TEXCOORD_0": result.push_accessor(&mut buffer_view, &mut accessor, &data.uv,       2, false),
+                        "COLOR_0":    result.push_accessor(&mut buffer_view, &mut accessor, &data.color,    4, false),
+                    },
+                    "indices": result.push_index(&mut buffer_view, &mut accessor, &data.index),
+                    "material": material,
+                }));
+            }
+
+            mesh.push(json!({ "name": format!("brush_{i}"), "primitives": primitive }));
+            node.push(json!({ "name": format!("brush_{i}"), "mesh": i }));
+        }
+
+        for entity in &world.entity {
+            let rotation = Quaternion::from_euler(
+                entity.rotation.x * DEG2RAD as f32,
+                entity.rotation.y * DEG2RAD as f32,
+                entity.rotation.z * DEG2RAD as f32,
+            );
+
+            let extras: serde_json::Map<String, Value> = entity
+                .meta
+                .data
+                .iter()
+                .map(|(key, value)| (key.clone(), value.kind.clone()))
+                .collect();
+
+            node.push(json!({
+                "name": entity.meta.name,
+                "translation": [entity.position.x, entity.position.y, entity.position.z],
+                "rotation": [rotation.x, rotation.y, rotation.z, rotation.w],
+                "scale": [entity.scale.x, entity.scale.y, entity.scale.z],
+                "extras": extras,
+            }));
+        }
+
+        let mut image = Vec::new();
+        let mut material = Vec::new();
+
+        for (i, t) in texture.iter().enumerate() {
+            image.push(result.push_image(&mut buffer_view, t));
+            material.push(json!({
+                "name": t.clone().unwrap_or("default".to_string()),
+                "pbrMetallicRoughness": {
+                    "baseColorTexture": { "index": i },
+                    "metallicFactor": 0.0,
+                    "roughnessFactor": 1.0,
+                },
+            }));
+        }
+
+        let texture: Vec<Value> = (0..texture.len())
+            .map(|i| json!({ "source": i, "sampler": 0 }))
+            .collect();
+
+        result.json = json!({
+            "asset": { "version": "2.0", "generator": "Mallet" },
+            "scene": 0,
+            "scenes": [{ "nodes": (0..node.len()).collect::<Vec<_>>() }],
+            "nodes": node,
+            "meshes": mesh,
+            "materials": material,
+            "textures": texture,
+            "images": image,
+            // nearest filter, repeat wrap.
+            "samplers": [{ "magFilter": 9728, "minFilter": 9728, "wrapS": 10497, "wrapT": 10497 }],
+            "accessors": accessor,
+            "bufferViews": buffer_view,
+            "buffers": [{ "byteLength": result.data.len() }],
+        });
+
+        result
+    }
+
+    // write the model to disk.
+    pub fn save_file(&self, path: &str) -> Result<(), String> {
+        let mut json =
+            serde_json::to_vec(&self.json).map_err(|e| format!("Glb::save_file(): {e}"))?;
+        let mut data = self.data.clone();
+
+        // every chunk must be aligned to 4 bytes. JSON is padded with white-space, binary with zero.
+        while json.len() % 4 != 0 {
+            json.push(b' ');
+        }
+        while !data.len().is_multiple_of(4) {
+            data.push(0);
+        }
+
+        let length = 12 + 8 + json.len() + 8 + data.len();
+        let mut file = Vec::with_capacity(length);
+
+        file.extend_from_slice(b"glTF");
+        file.extend_from_slice(&2_u32.to_le_bytes());
+        file.extend_from_slice(&(length as u32).to_le_bytes());
+        file.extend_from_slice(&(json.len() as u32).to_le_bytes());
+        file.extend_from_slice(b"JSON");
+        file.extend_from_slice(&json);
+        file.extend_from_slice(&(data.len() as u32).to_le_bytes());
+        file.extend_from_slice(b"BIN\0");
+        file.extend_from_slice(&data);
+
+        std::fs::write(path, file).map_err(|e| format!("Glb::save_file(): {e}"))
+    }
+
+    // push raw data to the binary buffer, and return the index of its buffer view.
+    fn push_buffer(
+        &mut self,
+        buffer_view: &mut Vec<Value>,
+        data: &[u8],
+        target: Option<u32>,
+    ) -> usize {
+        while !self.data.len().is_multiple_of(4) {
+            self.data.push(0);
+        }
+
+        let mut view =
+            json!({ "buffer": 0, "byteOffset": self.data.len(), "byteLength": data.len() });
+
+        if let Some(target) = target {
+            view["target"] = json!(target);
+        }
+
+        self.data.extend_from_slice(data);
+        buffer_view.push(view);
+
+        buffer_view.len() - 1
+    }
+
+    // push a list of float vectors to the binary buffer, and return the index of its accessor.
+    fn push_accessor(
+        &mut self,
+        buffer_view: &mut Vec<Value>,
+        accessor: &mut Vec<Value>,
+        data: &[f32],
+        size: usize,
+        bound: bool,
+    ) -> usize {
+        let byte: Vec<u8> = data.iter().flat_map(|v| v.to_le_bytes()).collect();
+        let view = self.push_buffer(buffer_view, &byte, Some(Self::TARGET_ARRAY));
+
+        let mut value = json!({
+            "bufferView": view,
+            "componentType": Self::KIND_FLOAT,
+            "count": data.len() / size,
+            "type": format!("VEC{size}"),
+        });
+
+        // the position accessor must have its bounds set.
+        if bound {
+            let mut min = vec![f32::MAX; size];
+            let mut max = vec![f32::MIN; size];
+
+            for chunk in data.chunks(size) {
+                for (i, v) in chunk.iter().enumerate() {
+                    min[i] = min[i].min(*v);
+                    max[i] = max[i].max(*v);
+                }
+            }
+
+            value["min"] = json!(min);
+            value["max"] = json!(max);
+        }
+
+        accessor.push(value);
+
+        accessor.len() - 1
+    }
+
+    // push a list of triangle indices to the binary buffer, and return the index of its accessor.
+    fn push_index(
+        &mut self,
+        buffer_view: &mut Vec<Value>,
+        accessor: &mut Vec<Value>,
+        data: &[u32],
+    ) -> usize {
+        let byte: Vec<u8> = data.iter().flat_map(|v| v.to_le_bytes()).collect();
+        let view = self.push_buffer(buffer_view, &byte, Some(Self::TARGET_INDEX));
+
+        accessor.push(json!({
+            "bufferView": view,
+            "componentType": Self::KIND_INDEX,
+            "count": data.len(),
+            "type": "SCALAR",
+        }));
+
+        accessor.len() - 1
+    }
+
+    // push a texture to the binary buffer, and return its image. a texture that can not be read is replaced with the default one.
+    fn push_image(&mut self, buffer_view: &mut Vec<Value>, texture: &Option<String>) -> Value {
+        let image = texture.as_deref().and_then(Self::image_data);
+
+        let (data, mime) = match &image {
+            Some((data, mime)) => (data.as_slice(), *mime),
+            None => (Inner::DEFAULT, "image/png"),
+        };
+
+        let view = self.push_buffer(buffer_view, data, None);
+        json!({ "bufferView": view, "mimeType": mime })
+    }
+
+    // read a texture from disk, alongside its MIME type. the format only takes PNG and JPEG, so any other is converted to PNG.
+    fn image_data(path: &str) -> Option<(Vec<u8>, &'static str)> {
+        let kind = std::path::Path::new(path)
+            .extension()
+            .map(|kind| kind.to_string_lossy().to_lowercase());
+
+        match kind.as_deref() {
+            Some("png") => return std::fs::read(path).ok().map(|data| (data, "image/png")),
+            Some("jpg") | Some("jpeg") => {
+                return std::fs::read(path).ok().map(|data| (data, "image/jpeg"))
+            }
+            _ => {}
+        }
+
+        let name = CString::new(path).ok()?;
+
+        unsafe {
+            let image = ffi::LoadImage(name.as_ptr());
+
+            if !ffi::IsImageValid(image) {
+                return None;
+            }
+
+            let mut size = 0;
+            let data = ffi::ExportImageToMemory(image, c".png".as_ptr(), &mut size);
+
+            ffi::UnloadImage(image);
+
+            if data.is_null() {
+                return None;
+            }
+
+            let result = std::slice::from_raw_parts(data, size as usize).to_vec();
+
+            ffi::MemFree(data.cast());
+
+            Some((result, "image/png"))
+        }
+    }
+}
+
+//================================================================
+
+// the vertex data of a primitive. every face has its own vertices, so that each can have its own normal and texture coordinate.
+#[derive(Default)]
+struct GlbPrimitive {
+    position: Vec<f32>,
+    normal: Vec<f32>,
+    uv: Vec<f32>,
+    color: Vec<f32>,
+    index: Vec<u32>,
+}
+
+impl GlbPrimitive {
+    fn push(&mut self, brush: &Brush, face: &Face, shape: Vector2) {
+        let start = (self.position.len() / 3) as u32;
+        let point = brush.point(face);
+        let normal = face.plane.normal;
+        let color = Vector4::new(
+            face.color.r as f32 / 255.0,
+            face.color.g as f32 / 255.0,
+            face.color.b as f32 / 255.0,
+            face.color.a as f32 / 255.0,
+        );
+
+        // the same texture coordinate as in Brush::draw. glTF has the origin at the top-left as well.
+        for (p, uv) in point.iter().zip(face.uv(&point, shape)) {
+            self.position.extend([p.x, p.y, p.z]);
+            self.normal.extend([normal.x, normal.y, normal.z]);
+            self.uv.extend([uv.x, uv.y]);
+            self.color.extend([color.x, color.y, color.z, color.w]);
+        }
+
+        // split the face into a triangle fan.
+        for i in 1..(point.len() as u32).saturating_sub(1) {
+            self.index.extend([start, start + i, start + i + 1]);
+        }
+    }
+}
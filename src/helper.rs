@@ -133,3 +133,13 @@ pub fn panic(text: &str) {
         .show();
     panic!("{}", text);
 }
+
+// show an error window, without closing Mallet.
+pub fn error(text: &str) {
+    rfd::MessageDialog::new()
+        .set_level(rfd::MessageLevel::Error)
+        .set_title("Error")
+        .set_description(text)
+        .set_buttons(rfd::MessageButtons::Ok)
+        .show();
+}
@@ -15,10 +15,20 @@
 * PERFORMANCE OF THIS SOFTWARE.
 */
 
+mod check;
 mod editor;
 mod game;
+mod geometry;
+mod glb;
 mod helper;
+mod map;
+mod marquee;
+mod obj;
+mod primitive;
+mod quake;
+mod selection;
 mod status;
+mod transform;
 mod window;
 
 //================================================================
@@ -46,12 +56,12 @@ fn main() {
             // success status: standard state.
             Status::Success(ref mut sub_state, ref mut asset, ref mut window, ref mut editor) => {
                 if let Some(state) = Status::success(&mut handle, &thread, sub_state, asset, window, editor) {
-                    status = state;
+                    status = Status::carry(std::mem::replace(&mut status, Status::Closure), state);
                 }
             }
             // failure status: an error has been thrown from Lua, show crash-handler.
-            Status::Failure(ref mut window, ref error) => {
-                if let Some(state) = Status::failure(&mut handle, &thread, window, error) {
+            Status::Failure(ref mut failure, ref mut asset, ref mut window) => {
+                if let Some(state) = Status::failure(&mut handle, &thread, failure, asset, window) {
                     status = state;
                 }
             }
@@ -0,0 +1,374 @@
+/*
+* BSD Zero Clause License
+*
+* Copyright (c) 2025 sockentrocken
+*
+* Permission to use, copy, modify, and/or distribute this software for any
+* purpose with or without fee is hereby granted.
+*
+* THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHOR DISCLAIMS ALL WARRANTIES WITH
+* REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF MERCHANTABILITY
+* AND FITNESS. IN NO EVENT SHALL THE AUTHOR BE LIABLE FOR ANY SPECIAL, DIRECT,
+* INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES WHATSOEVER RESULTING FROM
+* LOSS OF USE, DATA OR PROFITS, WHETHER IN AN ACTION OF CONTRACT, NEGLIGENCE OR
+* OTHER TORTIOUS ACTION, ARISING OUT OF OR IN CONNECTION WITH THE USE OR
+* PERFORMANCE OF THIS SOFTWARE.
+*/
+
+use crate::editor::*;
+use crate::game::*;
+
+//================================================================
+
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::thread::JoinHandle;
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+//================================================================
+
+// the native Mallet map file: a versioned snapshot of the world, alongside the path of the game it was made for.
+#[derive(Deserialize, Serialize)]
+pub struct Map {
+    pub version: u32,
+    pub game: String,
+    pub world: World,
+}
+
+impl Map {
+    pub const VERSION: u32 = 3;
+    pub const FILE_KIND: &'static str = "mallet";
+    pub const FILE_INFO: &'static str = "Mallet Map";
+
+    pub fn new(game: &Game, world: &World) -> Self {
+        Self {
+            version: Self::VERSION,
+            game: game.path.clone(),
+            world: world.clone(),
+        }
+    }
+
+    // read a map from disk, upgrading it to the current version if necessary.
+    pub fn new_from_file(path: &str) -> Result<Self, String> {
+        let data =
+            std::fs::read_to_string(path).map_err(|e| format!("Map::new_from_file(): {e}"))?;
+        let data: serde_json::Value =
+            serde_json::from_str(&data).map_err(|e| format!("Map::new_from_file(): {e}"))?;
+
+        let version = data.get("version").and_then(|v| v.as_u64()).ok_or(format!(
+            "Map::new_from_file(): \"{path}\" is not a Mallet map."
+        ))? as u32;
+
+        if version > Self::VERSION {
+            return Err(format!(
+                "Map::new_from_file(): \"{path}\" is of version {version}, but the newest supported version is {}.",
+                Self::VERSION
+            ));
+        }
+
+        let mut map: Self =
+            serde_json::from_value(data).map_err(|e| format!("Map::new_from_file(): {e}"))?;
+
+        // version 1: every face was a quad, without a plane.
+        if version < 2 {
+            for brush in &mut map.world.brush {
+                brush.set_plane();
+            }
+        }
+
+        // version 2: every brush, vertex, face and entity had a selection flag. it is ignored on load, so there is
+        // nothing to upgrade.
+
+        map.version = Self::VERSION;
+
+        Ok(map)
+    }
+
+    // write the map to disk.
+    pub fn save_file(&self, path: &str) -> Result<(), String> {
+        let data =
+            serde_json::to_string_pretty(self).map_err(|e| format!("Map::save_file(): {e}"))?;
+
+        std::fs::write(path, data).map_err(|e| format!("Map::save_file(): {e}"))
+    }
+
+    // ask the user for a map file to open.
+    pub fn pick_load() -> Option<String> {
+        rfd::FileDialog::new()
+            .add_filter(Self::FILE_INFO, &[Self::FILE_KIND])
+            .pick_file()
+            .map(|path| path.display().to_string())
+    }
+
+    // ask the user for a map file to save to. the native extension is appended if none was given.
+    pub fn pick_save() -> Option<String> {
+        rfd::FileDialog::new()
+            .add_filter(Self::FILE_INFO, &[Self::FILE_KIND])
+            .save_file()
+            .map(|mut path| {
+                if path.extension().is_none() {
+                    path.set_extension(Self::FILE_KIND);
+                }
+
+                path.display().to_string()
+            })
+    }
+}
+
+//================================================================
+
+// a list of every map file that has been recently opened or saved, newest first.
+#[derive(Default, Deserialize, Serialize)]
+pub struct Recent {
+    pub path: Vec<String>,
+}
+
+impl Recent {
+    pub const FILE_NAME: &'static str = "recent.json";
+    pub const COUNT: usize = 8;
+
+    // read the recent list from disk, or return an empty list if there is none.
+    pub fn new() -> Self {
+        std::fs::read_to_string(Self::FILE_NAME)
+            .ok()
+            .and_then(|data| serde_json::from_str(&data).ok())
+            .unwrap_or_default()
+    }
+
+    // move a map file to the front of the recent list, and write it to disk.
+    pub fn push(path: &str) -> Result<(), String> {
+        let mut recent = Self::new();
+
+        recent.path.retain(|p| p != path);
+        recent.path.insert(0, path.to_string());
+        recent.path.truncate(Self::COUNT);
+
+        let data =
+            serde_json::to_string_pretty(&recent).map_err(|e| format!("Recent::push(): {e}"))?;
+
+        std::fs::write(Self::FILE_NAME, data).map_err(|e| format!("Recent::push(): {e}"))
+    }
+}
+
+//================================================================
+
+// an autosave session of a running editor. every session writes its snapshots to its own folder, which is removed once the editor is closed cleanly.
+// a folder that is still there on startup belongs to an editor that was not, and can be restored.
+pub struct Recovery {
+    pub folder: PathBuf,
+    // if true, the folder is kept even after a clean close, such as when leaving the crash-handler.
+    pub keep: bool,
+    index: usize,
+    time: Instant,
+    data: Option<String>,
+    work: Option<JoinHandle<Result<(), String>>>,
+}
+
+impl Recovery {
+    pub const FOLDER: &'static str = "recovery";
+    pub const FILE_NAME: &'static str = "session.json";
+
+    pub fn new() -> Self {
+        let name = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|time| time.as_millis())
+            .unwrap_or_default();
+
+        Self {
+            folder: Path::new(Self::FOLDER).join(name.to_string()),
+            keep: false,
+            index: 0,
+            time: Instant::now(),
+            data: None,
+            work: None,
+        }
+    }
+
+    // write a snapshot of the world, once every "delay" seconds, but only if the world has changed since the last one.
+    // the first call only takes note of the world as it is, so that an untouched editor does not leave any snapshot behind.
+    pub fn update(
+        &mut self,
+        game: &Game,
+        world: &World,
+        path: &Option<String>,
+        delay: f32,
+        count: usize,
+    ) -> Result<(), String> {
+        self.join(false)?;
+
+        if self.data.is_none() {
+            self.data = Some(Self::data(game, world)?);
+            self.time = Instant::now();
+            return Ok(());
+        }
+
+        if delay <= 0.0 || self.time.elapsed().as_secs_f32() < delay {
+            return Ok(());
+        }
+
+        self.write(game, world, path, count)
+    }
+
+    // write a snapshot of the world right away. the file itself is written in the background.
+    pub fn write(
+        &mut self,
+        game: &Game,
+        world: &World,
+        path: &Option<String>,
+        count: usize,
+    ) -> Result<(), String> {
+        self.time = Instant::now();
+
+        let data = Self::data(game, world)?;
+
+        if self.data.as_ref() == Some(&data) {
+            return Ok(());
+        }
+
+        // only one snapshot may be written at once.
+        self.join(true)?;
+
+        let session = serde_json::to_string_pretty(&RecoverySession {
+            path: path.clone(),
+            file: PathBuf::default(),
+        })
+        .map_err(|e| format!("Recovery::write(): {e}"))?;
+
+        let folder = self.folder.clone();
+        let index = self.index;
+        let file = data.clone();
+
+        self.work = Some(std::thread::spawn(move || {
+            std::fs::create_dir_all(&folder).map_err(|e| format!("Recovery::write(): {e}"))?;
+            std::fs::write(folder.join(Self::FILE_NAME), session)
+                .map_err(|e| format!("Recovery::write(): {e}"))?;
+            std::fs::write(folder.join(Self::snapshot(index)), file)
+                .map_err(|e| format!("Recovery::write(): {e}"))?;
+
+            // rotate the snapshot list, removing the oldest one.
+            if index >= count.max(1) {
+                let _ = std::fs::remove_file(folder.join(Self::snapshot(index - count.max(1))));
+            }
+
+            Ok(())
+        }));
+
+        self.index += 1;
+        self.data = Some(data);
+
+        Ok(())
+    }
+
+    // get every session left behind by an editor that was not closed cleanly, newest first.
+    pub fn find() -> Vec<RecoverySession> {
+        let mut list = Vec::new();
+
+        let Ok(folder) = std::fs::read_dir(Self::FOLDER) else {
+            return list;
+        };
+
+        for entry in folder.flatten() {
+            let folder = entry.path();
+
+            let Some(mut session) = std::fs::read_to_string(folder.join(Self::FILE_NAME))
+                .ok()
+                .and_then(|data| serde_json::from_str::<RecoverySession>(&data).ok())
+            else {
+                continue;
+            };
+
+            // the newest snapshot is the one with the highest index.
+            let file = std::fs::read_dir(&folder).ok().and_then(|file| {
+                file.flatten()
+                    .filter_map(|entry| {
+                        let name = entry.file_name().to_string_lossy().to_string();
+                        let index = name
+                            .strip_prefix("autosave_")?
+                            .strip_suffix(&format!(".{}", Map::FILE_KIND))?
+                            .parse::<usize>()
+                            .ok()?;
+                        Some((index, entry.path()))
+                    })
+                    .max_by_key(|(index, _)| *index)
+            });
+
+            if let Some((_, file)) = file {
+                session.file = file;
+                list.push(session);
+            }
+        }
+
+        list.sort_by_key(|session| std::cmp::Reverse(session.time()));
+
+        list
+    }
+
+    // serialize the world as a native map file.
+    fn data(game: &Game, world: &World) -> Result<String, String> {
+        serde_json::to_string(&Map::new(game, world)).map_err(|e| format!("Recovery::data(): {e}"))
+    }
+
+    // get the file name of a snapshot.
+    fn snapshot(index: usize) -> String {
+        format!("autosave_{index}.{}", Map::FILE_KIND)
+    }
+
+    // wait for the last snapshot to be written. if "wait" is false, only check on it if it is already done.
+    fn join(&mut self, wait: bool) -> Result<(), String> {
+        if let Some(work) = self.work.take() {
+            if !wait && !work.is_finished() {
+                self.work = Some(work);
+                return Ok(());
+            }
+
+            return work
+                .join()
+                .map_err(|_| "Recovery::join(): the autosave thread has panicked.".to_string())?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Drop for Recovery {
+    fn drop(&mut self) {
+        let _ = self.join(true);
+
+        // a panic is not a clean close; leave the folder behind for the next start-up.
+        if !self.keep && !std::thread::panicking() {
+            let _ = std::fs::remove_dir_all(&self.folder);
+        }
+    }
+}
+
+//================================================================
+
+// the data of an autosave session, alongside the newest snapshot in it.
+#[derive(Deserialize, Serialize)]
+pub struct RecoverySession {
+    // the map file the editor was working on, if any.
+    pub path: Option<String>,
+    #[serde(skip)]
+    pub file: PathBuf,
+}
+
+impl RecoverySession {
+    // read the newest snapshot of the session.
+    pub fn map(&self) -> Result<Map, String> {
+        Map::new_from_file(&self.file.display().to_string())
+    }
+
+    // get the time the newest snapshot was written at.
+    pub fn time(&self) -> SystemTime {
+        std::fs::metadata(&self.file)
+            .and_then(|data| data.modified())
+            .unwrap_or(UNIX_EPOCH)
+    }
+
+    // remove the session from disk.
+    pub fn remove(&self) {
+        if let Some(folder) = self.file.parent() {
+            let _ = std::fs::remove_dir_all(folder);
+        }
+    }
+}
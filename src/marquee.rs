@@ -0,0 +1,419 @@
+/*
+* BSD Zero Clause License
+*
+* Copyright (c) 2025 sockentrocken
+*
+* Permission to use, copy, modify, and/or distribute this software for any
+* purpose with or without fee is hereby granted.
+*
+* THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHOR DISCLAIMS ALL WARRANTIES WITH
+* REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF MERCHANTABILITY
+* AND FITNESS. IN NO EVENT SHALL THE AUTHOR BE LIABLE FOR ANY SPECIAL, DIRECT,
+* INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES WHATSOEVER RESULTING FROM
+* LOSS OF USE, DATA OR PROFITS, WHETHER IN AN ACTION OF CONTRACT, NEGLIGENCE OR
+* OTHER TORTIOUS ACTION, ARISING OUT OF OR IN CONNECTION WITH THE USE OR
+* PERFORMANCE OF THIS SOFTWARE.
+*/
+
+use crate::editor::*;
+use crate::selection::*;
+
+//================================================================
+
+use raylib::prelude::*;
+
+//================================================================
+
+// how a marquee changes the selection.
+#[derive(Copy, Clone, PartialEq)]
+pub enum MarqueeMode {
+    // select whatever is in the marquee, and nothing else.
+    Replace,
+    // select whatever is in the marquee, keeping the current selection.
+    Add,
+    // deselect whatever is in the marquee.
+    Subtract,
+}
+
+impl MarqueeMode {
+    // get the mode from the add and subtract modifiers. subtract wins if both are down.
+    pub fn new(user: &User, handle: &RaylibHandle) -> Self {
+        if user.select_subtract.get_down(handle) {
+            Self::Subtract
+        } else if user.select_add.get_down(handle) {
+            Self::Add
+        } else {
+            Self::Replace
+        }
+    }
+
+    // get the new selection state of something, from its current state and whether it is in the marquee.
+    fn apply(&self, focus: bool, inside: bool) -> bool {
+        match self {
+            Self::Replace => inside,
+            Self::Add => focus || inside,
+            Self::Subtract => focus && !inside,
+        }
+    }
+}
+
+//================================================================
+
+// a rubber-band selection: a rectangle in an orthographic view, or a free-hand lasso in a perspective view.
+// every point is in the space of the view it was begun in.
+#[derive(Default)]
+pub struct Marquee {
+    view: Option<usize>,
+    point: Vec<Vector2>,
+    lasso: bool,
+    drag: bool,
+}
+
+impl Marquee {
+    // distance the mouse must move by, in pixels, before a click becomes a drag.
+    const DRAG_RANGE: f32 = 4.0;
+    // minimum distance between two points of a lasso, in pixels.
+    const LASSO_STEP: f32 = 8.0;
+    const COLOR: Color = Color::new(0, 121, 241, 255);
+
+    // begin a marquee at a point of a view.
+    pub fn begin(&mut self, view: usize, camera: &Camera3D, point: Vector2) {
+        self.view = Some(view);
+        self.point = vec![point];
+        self.lasso = camera.camera_type() == CameraProjection::CAMERA_PERSPECTIVE;
+        self.drag = false;
+    }
+
+    // get the view the marquee was begun in, if any.
+    pub fn view(&self) -> Option<usize> {
+        self.view
+    }
+
+    // drag the marquee to a point of its view.
+    pub fn update(&mut self, point: Vector2) {
+        let Some(first) = self.point.first().copied() else {
+            return;
+        };
+
+        if !self.drag && first.distance_to(point) < Self::DRAG_RANGE {
+            return;
+        }
+
+        self.drag = true;
+
+        if self.lasso {
+            if self
+                .point
+                .last()
+                .is_some_and(|p| p.distance_to(point) >= Self::LASSO_STEP)
+            {
+                self.point.push(point);
+            }
+        } else {
+            self.point = vec![first, point];
+        }
+    }
+
+    // let go of the marquee, changing the selection based on the widget: every brush and entity for a transform widget,
+    // or every vertex, edge or face of every selected brush otherwise. something is in the marquee if any part of it is.
+    // a marquee that was never dragged is a click on nothing, which deselects everything unless a modifier is down.
+    pub fn end(
+        &mut self,
+        world: &mut World,
+        user: &User,
+        widget: &Widget,
+        handle: &RaylibHandle,
+        camera: &Camera3D,
+        port: Vector2,
+    ) {
+        let mode = MarqueeMode::new(user, handle);
+        let shape = self.shape();
+        let drag = self.drag;
+
+        self.view = None;
+        self.point.clear();
+        self.drag = false;
+
+        if !drag || shape.len() < 3 {
+            if mode == MarqueeMode::Replace {
+                world.select_none();
+            }
+
+            return;
+        }
+
+        let project = |point: Vector3| Self::project(handle, camera, port, point);
+        let mut list = Vec::new();
+
+        match widget {
+            Widget::Position | Widget::Rotation | Widget::Scale => {
+                for (i, brush) in world.brush.iter().enumerate() {
+                    let point: Vec<Option<Vector2>> =
+                        brush.vertex.iter().map(|v| project(v.point)).collect();
+
+                    list.push((
+                        SelectionItem::Brush(i),
+                        Self::touch(&shape, &point, &brush.edge_list()),
+                    ));
+                }
+
+                for (i, entity) in world.entity.iter().enumerate() {
+                    let bound = entity.bound_box();
+                    let (a, b) = (bound.min, bound.max);
+
+                    #[rustfmt::skip]
+                    let point: Vec<Option<Vector2>> = [
+                        Vector3::new(a.x, a.y, a.z), Vector3::new(b.x, a.y, a.z),
+                        Vector3::new(b.x, b.y, a.z), Vector3::new(a.x, b.y, a.z),
+                        Vector3::new(a.x, a.y, b.z), Vector3::new(b.x, a.y, b.z),
+                        Vector3::new(b.x, b.y, b.z), Vector3::new(a.x, b.y, b.z),
+                    ]
+                    .into_iter()
+                    .map(project)
+                    .collect();
+
+                    #[rustfmt::skip]
+                    let edge = [
+                        (0, 1), (1, 2), (2, 3), (3, 0),
+                        (4, 5), (5, 6), (6, 7), (7, 4),
+                        (0, 4), (1, 5), (2, 6), (3, 7),
+                    ];
+
+                    list.push((SelectionItem::Entity(i), Self::touch(&shape, &point, &edge)));
+                }
+
+                // a brush that is deselected also loses every part of it, and a new selection starts from nothing.
+                if mode == MarqueeMode::Replace {
+                    world.select_none();
+                }
+            }
+            Widget::Vertex | Widget::Edge | Widget::Face => {
+                for i in world.selection.brush_list() {
+                    let brush = &world.brush[i];
+                    let point: Vec<Option<Vector2>> =
+                        brush.vertex.iter().map(|v| project(v.point)).collect();
+
+                    match widget {
+                        Widget::Vertex => {
+                            for (j, p) in point.iter().enumerate() {
+                                let inside = p.is_some_and(|p| Self::inside(&shape, p));
+                                list.push((SelectionItem::Vertex(i, j), inside));
+                            }
+                        }
+                        Widget::Edge => {
+                            for edge in brush.edge_list() {
+                                list.push((
+                                    SelectionItem::Edge(i, edge),
+                                    Self::touch(&shape, &point, &[edge]),
+                                ));
+                            }
+                        }
+                        _ => {
+                            for (j, face) in brush.face.iter().enumerate() {
+                                let edge: Vec<(usize, usize)> = (0..face.index.len())
+                                    .map(|k| {
+                                        (face.index[k], face.index[(k + 1) % face.index.len()])
+                                    })
+                                    .collect();
+
+                                list.push((
+                                    SelectionItem::Face(i, j),
+                                    Self::touch(&shape, &point, &edge),
+                                ));
+                            }
+                        }
+                    }
+                }
+
+                // every brush stays selected, as the marquee only picks from the parts of the selected brushes.
+                if mode == MarqueeMode::Replace {
+                    world.selection.remove_part_all();
+                }
+            }
+            Widget::Clip => {}
+        }
+
+        for (item, inside) in list {
+            let focus = mode.apply(world.selection.has(item), inside);
+            world.selection.set(item, focus);
+        }
+    }
+
+    // draw the marquee, if it was begun in the view.
+    pub fn draw(&self, draw: &mut RaylibMode2D<RaylibTextureMode<RaylibDrawHandle>>, view: usize) {
+        if self.view != Some(view) || !self.drag {
+            return;
+        }
+
+        let shape = self.shape();
+
+        if !self.lasso {
+            if let (Some(a), Some(b)) = (shape.first(), shape.get(2)) {
+                let rectangle = Rectangle::new(
+                    a.x.min(b.x),
+                    a.y.min(b.y),
+                    (a.x - b.x).abs(),
+                    (a.y - b.y).abs(),
+                );
+
+                draw.draw_rectangle_rec(rectangle, Self::COLOR.alpha(0.25));
+            }
+        }
+
+        for (i, a) in shape.iter().enumerate() {
+            draw.draw_line_v(*a, shape[(i + 1) % shape.len()], Self::COLOR);
+        }
+    }
+
+    // get the polygon of the marquee.
+    fn shape(&self) -> Vec<Vector2> {
+        match (self.lasso, self.point.as_slice()) {
+            (false, [a, b]) => vec![*a, Vector2::new(b.x, a.y), *b, Vector2::new(a.x, b.y)],
+            (false, _) => Vec::new(),
+            (true, _) => self.point.clone(),
+        }
+    }
+
+    // get the point of a view a point of the world is drawn at, if it is not behind the camera.
+    fn project(
+        handle: &RaylibHandle,
+        camera: &Camera3D,
+        port: Vector2,
+        point: Vector3,
+    ) -> Option<Vector2> {
+        if camera.camera_type() == CameraProjection::CAMERA_PERSPECTIVE
+            && (point - camera.position).dot(camera.target - camera.position) <= 0.0
+        {
+            return None;
+        }
+
+        Some(handle.get_world_to_screen_ex(point, camera, port.x as i32, port.y as i32))
+    }
+
+    // check if a point is inside a polygon, with the even-odd rule, so that a lasso that crosses itself works as expected.
+    fn inside(shape: &[Vector2], point: Vector2) -> bool {
+        let mut inside = false;
+
+        for (i, a) in shape.iter().enumerate() {
+            let b = shape[(i + 1) % shape.len()];
+
+            if (a.y > point.y) != (b.y > point.y)
+                && point.x < a.x + (point.y - a.y) / (b.y - a.y) * (b.x - a.x)
+            {
+                inside = !inside;
+            }
+        }
+
+        inside
+    }
+
+    // check if two line segments cross.
+    fn cross(a: Vector2, b: Vector2, c: Vector2, d: Vector2) -> bool {
+        let side = |p: Vector2, q: Vector2, r: Vector2| {
+            (q.x - p.x) * (r.y - p.y) - (q.y - p.y) * (r.x - p.x)
+        };
+
+        let ab_c = side(a, b, c);
+        let ab_d = side(a, b, d);
+        let cd_a = side(c, d, a);
+        let cd_b = side(c, d, b);
+
+        (ab_c > 0.0) != (ab_d > 0.0) && (cd_a > 0.0) != (cd_b > 0.0)
+    }
+
+    // check if any part of a wire-frame is in a polygon: any point of an edge, or any edge crossing the polygon.
+    fn touch(shape: &[Vector2], point: &[Option<Vector2>], edge: &[(usize, usize)]) -> bool {
+        edge.iter().any(|(a, b)| match (point[*a], point[*b]) {
+            (Some(a), Some(b)) => {
+                Self::inside(shape, a)
+                    || Self::inside(shape, b)
+                    || shape
+                        .iter()
+                        .enumerate()
+                        .any(|(i, c)| Self::cross(a, b, *c, shape[(i + 1) % shape.len()]))
+            }
+            (Some(p), None) | (None, Some(p)) => Self::inside(shape, p),
+            (None, None) => false,
+        })
+    }
+}
+
+//================================================================
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    // a lasso that crosses itself leaves out the part it loops over twice.
+    #[test]
+    fn inside() {
+        let square = [
+            Vector2::new(0.0, 0.0),
+            Vector2::new(10.0, 0.0),
+            Vector2::new(10.0, 10.0),
+            Vector2::new(0.0, 10.0),
+        ];
+
+        assert!(Marquee::inside(&square, Vector2::new(5.0, 5.0)));
+        assert!(!Marquee::inside(&square, Vector2::new(15.0, 5.0)));
+        assert!(!Marquee::inside(&square, Vector2::new(5.0, -5.0)));
+
+        let bow = [
+            Vector2::new(0.0, 0.0),
+            Vector2::new(10.0, 10.0),
+            Vector2::new(10.0, 0.0),
+            Vector2::new(0.0, 10.0),
+        ];
+
+        assert!(Marquee::inside(&bow, Vector2::new(2.0, 5.0)));
+        assert!(!Marquee::inside(&bow, Vector2::new(5.0, 2.0)));
+    }
+
+    #[test]
+    fn cross() {
+        let a = Vector2::new(0.0, 0.0);
+        let b = Vector2::new(10.0, 10.0);
+
+        assert!(Marquee::cross(
+            a,
+            b,
+            Vector2::new(0.0, 10.0),
+            Vector2::new(10.0, 0.0)
+        ));
+        assert!(!Marquee::cross(
+            a,
+            b,
+            Vector2::new(0.0, 1.0),
+            Vector2::new(9.0, 10.0)
+        ));
+        assert!(!Marquee::cross(
+            a,
+            b,
+            Vector2::new(20.0, 0.0),
+            Vector2::new(11.0, 9.0)
+        ));
+    }
+
+    // an edge that runs through the marquee touches it, even with both of its points outside of it.
+    #[test]
+    fn touch() {
+        let square = [
+            Vector2::new(0.0, 0.0),
+            Vector2::new(10.0, 0.0),
+            Vector2::new(10.0, 10.0),
+            Vector2::new(0.0, 10.0),
+        ];
+        let edge = [(0, 1)];
+
+        let through = [Some(Vector2::new(-5.0, 5.0)), Some(Vector2::new(15.0, 5.0))];
+        let beside = [
+            Some(Vector2::new(-5.0, 5.0)),
+            Some(Vector2::new(-5.0, 15.0)),
+        ];
+        let behind = [Some(Vector2::new(5.0, 5.0)), None];
+
+        assert!(Marquee::touch(&square, &through, &edge));
+        assert!(!Marquee::touch(&square, &beside, &edge));
+        assert!(Marquee::touch(&square, &behind, &edge));
+    }
+}
@@ -0,0 +1,269 @@
+/*
+* BSD Zero Clause License
+*
+* Copyright (c) 2025 sockentrocken
+*
+* Permission to use, copy, modify, and/or distribute this software for any
+* purpose with or without fee is hereby granted.
+*
+* THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHOR DISCLAIMS ALL WARRANTIES WITH
+* REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF MERCHANTABILITY
+* AND FITNESS. IN NO EVENT SHALL THE AUTHOR BE LIABLE FOR ANY SPECIAL, DIRECT,
+* INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES WHATSOEVER RESULTING FROM
+* LOSS OF USE, DATA OR PROFITS, WHETHER IN AN ACTION OF CONTRACT, NEGLIGENCE OR
+* OTHER TORTIOUS ACTION, ARISING OUT OF OR IN CONNECTION WITH THE USE OR
+* PERFORMANCE OF THIS SOFTWARE.
+*/
+
+use crate::editor::*;
+use crate::game::*;
+use crate::quake::*;
+
+//================================================================
+
+use raylib::prelude::*;
+use std::fmt;
+
+//================================================================
+
+// a Wavefront OBJ model, with a MTL material library. every brush is an object, and every texture is a material.
+pub struct Obj {
+    pub material: Vec<ObjMaterial>,
+    pub mesh: Vec<ObjMesh>,
+    pub entity: Vec<(String, Vector3)>,
+}
+
+impl Obj {
+    pub const FILE_KIND: &'static str = "obj";
+    pub const FILE_INFO: &'static str = "Wavefront OBJ";
+    pub const LIBRARY_KIND: &'static str = "mtl";
+
+    // convert the world to a model. if entity is true, every entity is also written as a named point.
+    pub fn new_from_world(world: &World, game: &Game, asset: &Asset, entity: bool) -> Self {
+        let mut material: Vec<ObjMaterial> = Vec::new();
+        let mut mesh = Vec::new();
+
+        for (i, brush) in world.brush.iter().enumerate() {
+            let mut face = Vec::new();
+
+            for f in &brush.face {
+                let index = match material.iter().position(|m| m.texture == f.texture) {
+                    Some(index) => index,
+                    None => {
+                        material.push(ObjMaterial::new(game, &f.texture, &material));
+                        material.len() - 1
+                    }
+                };
+
+                face.push(ObjFace::new_from_face(
+                    brush,
+                    f,
+                    index,
+                    asset.texture_shape(&f.texture),
+                ));
+            }
+
+            // group every face by material.
+            face.sort_by_key(|f| f.material);
+
+            mesh.push(ObjMesh {
+                name: format!("brush_{i}"),
+                vertex: brush.vertex.iter().map(|v| v.point).collect(),
+                face,
+            });
+        }
+
+        let entity = {
+            if entity {
+                world
+                    .entity
+                    .iter()
+                    .enumerate()
+                    .map(|(i, e)| (format!("{}_{i}", Obj::name(&e.meta.name)), e.position))
+                    .collect()
+            } else {
+                Vec::new()
+            }
+        };
+
+        Self {
+            material,
+            mesh,
+            entity,
+        }
+    }
+
+    // write the model to disk, alongside the material library, which will have the same name with the MTL extension.
+    pub fn save_file(&self, path: &str) -> Result<(), String> {
+        let library = std::path::Path::new(path).with_extension(Self::LIBRARY_KIND);
+        let library_name = library
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default();
+
+        let mut data = String::new();
+
+        for material in &self.material {
+            data.push_str(&material.to_string());
+        }
+
+        std::fs::write(&library, data).map_err(|e| format!("Obj::save_file(): {e}"))?;
+        std::fs::write(path, format!("mtllib {library_name}\n{self}"))
+            .map_err(|e| format!("Obj::save_file(): {e}"))
+    }
+
+    // get a name without any white-space, which would otherwise break the format.
+    fn name(name: &str) -> String {
+        name.split_whitespace().collect::<Vec<_>>().join("_")
+    }
+}
+
+impl fmt::Display for Obj {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        // OBJ indices are global and start at 1.
+        let mut vertex = 1;
+        let mut uv = 1;
+        let mut normal = 1;
+
+        for mesh in &self.mesh {
+            writeln!(f, "o {}", mesh.name)?;
+
+            for v in &mesh.vertex {
+                writeln!(f, "v {} {} {}", v.x, v.y, v.z)?;
+            }
+
+            let mut material = None;
+
+            for face in &mesh.face {
+                for v in &face.uv {
+                    writeln!(f, "vt {} {}", v.x, v.y)?;
+                }
+
+                writeln!(
+                    f,
+                    "vn {} {} {}",
+                    face.normal.x, face.normal.y, face.normal.z
+                )?;
+
+                if material != Some(face.material) {
+                    writeln!(f, "usemtl {}", self.material[face.material].name)?;
+                    material = Some(face.material);
+                }
+
+                // split the face into a triangle fan.
+                for i in 1..face.index.len().saturating_sub(1) {
+                    write!(f, "f")?;
+
+                    for j in [0, i, i + 1] {
+                        write!(f, " {}/{}/{normal}", vertex + face.index[j], uv + j)?;
+                    }
+
+                    writeln!(f)?;
+                }
+
+                uv += face.uv.len();
+                normal += 1;
+            }
+
+            vertex += mesh.vertex.len();
+        }
+
+        for (name, point) in &self.entity {
+            writeln!(f, "o {name}")?;
+            writeln!(f, "v {} {} {}", point.x, point.y, point.z)?;
+            writeln!(f, "p -1")?;
+        }
+
+        Ok(())
+    }
+}
+
+//================================================================
+
+pub struct ObjMaterial {
+    pub name: String,
+    pub texture: Option<String>,
+}
+
+impl ObjMaterial {
+    // create a material for a texture. two textures may end up with the same name, such as "wall.png" and "wall.tga", so a
+    // number is added to the name of any material after the first, to keep every name in "list" unique.
+    pub fn new(game: &Game, texture: &Option<String>, list: &[ObjMaterial]) -> Self {
+        let base = {
+            if let Some(texture) = texture {
+                Obj::name(&Quake::texture_name(game, texture))
+            } else {
+                Quake::TEXTURE_DEFAULT.to_string()
+            }
+        };
+
+        let mut name = base.clone();
+        let mut count = 1;
+
+        while list.iter().any(|m| m.name == name) {
+            name = format!("{base}_{count}");
+            count += 1;
+        }
+
+        Self {
+            name,
+            texture: texture.clone(),
+        }
+    }
+}
+
+impl fmt::Display for ObjMaterial {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "newmtl {}", self.name)?;
+        writeln!(f, "Kd 1 1 1")?;
+
+        // point to the texture file of the game, which is only known if the face does have a texture.
+        if let Some(texture) = &self.texture {
+            let path = std::fs::canonicalize(texture)
+                .map(|path| path.display().to_string())
+                .unwrap_or(texture.clone());
+
+            writeln!(f, "map_Kd {path}")?;
+        }
+
+        writeln!(f)
+    }
+}
+
+//================================================================
+
+pub struct ObjMesh {
+    pub name: String,
+    pub vertex: Vec<Vector3>,
+    pub face: Vec<ObjFace>,
+}
+
+//================================================================
+
+pub struct ObjFace {
+    pub material: usize,
+    pub index: Vec<usize>,
+    pub uv: Vec<Vector2>,
+    pub normal: Vector3,
+}
+
+impl ObjFace {
+    pub fn new_from_face(brush: &Brush, face: &Face, material: usize, shape: Vector2) -> Self {
+        let point = brush.point(face);
+        let normal = face.plane.normal;
+
+        // the same texture coordinate as in Brush::draw, but OBJ has the origin at the bottom-left, rather than at the top-left.
+        let uv = face
+            .uv(&point, shape)
+            .iter()
+            .map(|uv| Vector2::new(uv.x, 1.0 - uv.y))
+            .collect();
+
+        Self {
+            material,
+            index: face.index.clone(),
+            uv,
+            normal,
+        }
+    }
+}
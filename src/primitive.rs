@@ -0,0 +1,276 @@
+/*
+* BSD Zero Clause License
+*
+* Copyright (c) 2025 sockentrocken
+*
+* Permission to use, copy, modify, and/or distribute this software for any
+* purpose with or without fee is hereby granted.
+*
+* THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHOR DISCLAIMS ALL WARRANTIES WITH
+* REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF MERCHANTABILITY
+* AND FITNESS. IN NO EVENT SHALL THE AUTHOR BE LIABLE FOR ANY SPECIAL, DIRECT,
+* INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES WHATSOEVER RESULTING FROM
+* LOSS OF USE, DATA OR PROFITS, WHETHER IN AN ACTION OF CONTRACT, NEGLIGENCE OR
+* OTHER TORTIOUS ACTION, ARISING OUT OF OR IN CONNECTION WITH THE USE OR
+* PERFORMANCE OF THIS SOFTWARE.
+*/
+
+use crate::editor::*;
+
+//================================================================
+
+use raylib::prelude::*;
+
+//================================================================
+
+// a primitive brush shape.
+#[derive(Copy, Clone)]
+pub enum PrimitiveKind {
+    Cylinder,
+    Cone,
+    Wedge,
+    Sphere,
+    Arch,
+    Stair,
+}
+
+impl PrimitiveKind {
+    pub const LIST: [Self; 6] = [
+        Self::Cylinder,
+        Self::Cone,
+        Self::Wedge,
+        Self::Sphere,
+        Self::Arch,
+        Self::Stair,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Cylinder => "Cylinder",
+            Self::Cone => "Cone",
+            Self::Wedge => "Wedge",
+            Self::Sphere => "Sphere",
+            Self::Arch => "Arch",
+            Self::Stair => "Stair",
+        }
+    }
+}
+
+//================================================================
+
+// the parameter list of every primitive generator. every shape is centered on the origin, with Y as up.
+#[derive(Clone)]
+pub struct Primitive {
+    // side count of a cylinder, cone or sphere, and segment count of an arch.
+    pub side: f32,
+    // radius of a cylinder, cone, sphere or arch, and half the width of a wedge or stair.
+    pub radius: f32,
+    // height of every shape but the sphere. for an arch, this is its depth.
+    pub height: f32,
+    // step count of a stair.
+    pub step: f32,
+    // angle an arch runs over, in degrees.
+    pub angle: f32,
+    // thickness of an arch.
+    pub thickness: f32,
+}
+
+impl Primitive {
+    // create the brush list for a primitive. every convex shape is a single brush; an arch or a stair is a brush per segment or step.
+    pub fn create(&self, kind: PrimitiveKind) -> Vec<Brush> {
+        let side = (self.side.round() as usize).max(3);
+        let step = (self.step.round() as usize).max(1);
+        let radius = self.radius.abs().max(0.01);
+        let height = self.height.abs().max(0.01);
+        let angle = self.angle.clamp(1.0, 360.0) * DEG2RAD as f32;
+        // the thickness must stay below the radius, or the inner ring of an arch collapses into the origin.
+        let thickness = self.thickness.abs().max(0.01).min(radius * 0.99);
+        let half = height * 0.5;
+
+        match kind {
+            PrimitiveKind::Cylinder => {
+                let mut point = Self::ring(side, radius, -half);
+                point.extend(Self::ring(side, radius, half));
+
+                let mut face = vec![(0..side).collect(), (side..side * 2).collect()];
+
+                for i in 0..side {
+                    let j = (i + 1) % side;
+                    face.push(vec![i, j, side + j, side + i]);
+                }
+
+                vec![Self::brush(point, face)]
+            }
+            PrimitiveKind::Cone => {
+                let mut point = Self::ring(side, radius, -half);
+                point.push(Vector3::new(0.0, half, 0.0));
+
+                let mut face = vec![(0..side).collect()];
+
+                for i in 0..side {
+                    face.push(vec![i, (i + 1) % side, side]);
+                }
+
+                vec![Self::brush(point, face)]
+            }
+            PrimitiveKind::Wedge => {
+                #[rustfmt::skip]
+                let point = vec![
+                    Vector3::new(-radius, -half, -radius),
+                    Vector3::new( radius, -half, -radius),
+                    Vector3::new( radius, -half,  radius),
+                    Vector3::new(-radius, -half,  radius),
+                    Vector3::new(-radius,  half, -radius),
+                    Vector3::new( radius,  half, -radius),
+                ];
+
+                let face = vec![
+                    vec![0, 1, 2, 3],
+                    vec![0, 1, 5, 4],
+                    vec![3, 2, 5, 4],
+                    vec![0, 3, 4],
+                    vec![1, 2, 5],
+                ];
+
+                vec![Self::brush(point, face)]
+            }
+            PrimitiveKind::Sphere => {
+                // every ring, from the bottom pole to the top pole.
+                let ring = (side / 2).max(2);
+                let mut point = vec![Vector3::new(0.0, -radius, 0.0)];
+
+                for i in 1..ring {
+                    let pitch = (i as f32 / ring as f32) * PI as f32 - PI as f32 * 0.5;
+                    point.extend(Self::ring(side, radius * pitch.cos(), radius * pitch.sin()));
+                }
+
+                point.push(Vector3::new(0.0, radius, 0.0));
+
+                let top = point.len() - 1;
+                let at = |r: usize, i: usize| 1 + r * side + (i % side);
+                let mut face = Vec::new();
+
+                for i in 0..side {
+                    face.push(vec![0, at(0, i), at(0, i + 1)]);
+                    face.push(vec![top, at(ring - 2, i), at(ring - 2, i + 1)]);
+
+                    for r in 0..ring - 2 {
+                        face.push(vec![at(r, i), at(r, i + 1), at(r + 1, i + 1), at(r + 1, i)]);
+                    }
+                }
+
+                vec![Self::brush(point, face)]
+            }
+            PrimitiveKind::Arch => {
+                // the arch stands on the XY plane, running counter-clock-wise from the +X axis.
+                (0..side)
+                    .map(|i| {
+                        let a = angle * (i as f32 / side as f32);
+                        let b = angle * ((i + 1) as f32 / side as f32);
+                        let mut point = Vec::new();
+
+                        for (angle, radius) in [
+                            (a, radius),
+                            (b, radius),
+                            (b, radius - thickness),
+                            (a, radius - thickness),
+                        ] {
+                            point.push(Vector3::new(
+                                angle.cos() * radius,
+                                angle.sin() * radius,
+                                -half,
+                            ));
+                        }
+                        for i in 0..4 {
+                            point.push(point[i] + Vector3::new(0.0, 0.0, height));
+                        }
+
+                        Self::brush(point, Self::box_face())
+                    })
+                    .collect()
+            }
+            PrimitiveKind::Stair => {
+                // every step runs from the floor up, rising towards +Z.
+                let depth = radius * 2.0 / step as f32;
+
+                (0..step)
+                    .map(|i| {
+                        let top = -half + height * ((i + 1) as f32 / step as f32);
+                        let min = Vector3::new(-radius, -half, -radius + depth * i as f32);
+                        let max = Vector3::new(radius, top, -radius + depth * (i + 1) as f32);
+
+                        #[rustfmt::skip]
+                        let point = vec![
+                            Vector3::new(min.x, min.y, min.z),
+                            Vector3::new(max.x, min.y, min.z),
+                            Vector3::new(max.x, max.y, min.z),
+                            Vector3::new(min.x, max.y, min.z),
+                            Vector3::new(min.x, min.y, max.z),
+                            Vector3::new(max.x, min.y, max.z),
+                            Vector3::new(max.x, max.y, max.z),
+                            Vector3::new(min.x, max.y, max.z),
+                        ];
+
+                        Self::brush(point, Self::box_face())
+                    })
+                    .collect()
+            }
+        }
+    }
+
+    // get a ring of points around the Y axis.
+    fn ring(side: usize, radius: f32, y: f32) -> Vec<Vector3> {
+        (0..side)
+            .map(|i| {
+                let angle = (i as f32 / side as f32) * PI as f32 * 2.0;
+                Vector3::new(angle.cos() * radius, y, angle.sin() * radius)
+            })
+            .collect()
+    }
+
+    // get the face list of a hexahedron, where the first four points are the front and the last four the back, in the same order.
+    fn box_face() -> Vec<Vec<usize>> {
+        vec![
+            vec![0, 1, 2, 3],
+            vec![4, 5, 6, 7],
+            vec![0, 1, 5, 4],
+            vec![1, 2, 6, 5],
+            vec![2, 3, 7, 6],
+            vec![3, 0, 4, 7],
+        ]
+    }
+
+    // create a convex brush from a point list and a face list, turning every face to be counter-clock-wise as seen from outside.
+    fn brush(point: Vec<Vector3>, face: Vec<Vec<usize>>) -> Brush {
+        let center = point.iter().fold(Vector3::zero(), |a, b| a + *b) / point.len() as f32;
+
+        let face = face
+            .into_iter()
+            .map(|mut index| {
+                let a = point[index[0]];
+                let normal = (point[index[1]] - a).cross(point[index[2]] - a);
+
+                if normal.dot(a - center) < 0.0 {
+                    index.reverse();
+                }
+
+                Face::new(index)
+            })
+            .collect();
+
+        Brush::new(&point, face)
+    }
+}
+
+impl Default for Primitive {
+    fn default() -> Self {
+        Self {
+            side: 8.0,
+            radius: 2.0,
+            height: 4.0,
+            step: 8.0,
+            angle: 180.0,
+            thickness: 1.0,
+        }
+    }
+}
@@ -0,0 +1,1046 @@
+/*
+* BSD Zero Clause License
+*
+* Copyright (c) 2025 sockentrocken
+*
+* Permission to use, copy, modify, and/or distribute this software for any
+* purpose with or without fee is hereby granted.
+*
+* THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHOR DISCLAIMS ALL WARRANTIES WITH
+* REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF MERCHANTABILITY
+* AND FITNESS. IN NO EVENT SHALL THE AUTHOR BE LIABLE FOR ANY SPECIAL, DIRECT,
+* INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES WHATSOEVER RESULTING FROM
+* LOSS OF USE, DATA OR PROFITS, WHETHER IN AN ACTION OF CONTRACT, NEGLIGENCE OR
+* OTHER TORTIOUS ACTION, ARISING OUT OF OR IN CONNECTION WITH THE USE OR
+* PERFORMANCE OF THIS SOFTWARE.
+*/
+
+use crate::editor::*;
+use crate::game::*;
+use crate::geometry::*;
+
+//================================================================
+
+use raylib::prelude::*;
+use std::{collections::HashMap, fmt};
+
+//================================================================
+
+// a Quake map, in the Valve 220 format. the first entity is always the world-spawn, which holds every brush.
+pub struct Quake {
+    pub entity: Vec<QuakeEntity>,
+}
+
+impl Quake {
+    pub const FILE_KIND: &'static str = "map";
+    pub const FILE_INFO: &'static str = "Quake Map (Valve 220)";
+    // Quake units per Mallet unit. the default brush becomes the usual 64 unit cube.
+    pub const SCALE: f32 = 32.0;
+    // texture name for a face without a texture.
+    pub const TEXTURE_DEFAULT: &'static str = "default";
+
+    pub fn new_from_world(world: &World, game: &Game, asset: &Asset) -> Self {
+        let mut spawn = QuakeEntity::new("worldspawn");
+
+        spawn
+            .data
+            .push(("mapversion".to_string(), "220".to_string()));
+
+        for brush in &world.brush {
+            spawn
+                .brush
+                .push(QuakeBrush::new_from_brush(brush, game, asset));
+        }
+
+        let mut entity = vec![spawn];
+
+        for e in &world.entity {
+            entity.push(QuakeEntity::new_from_entity(e));
+        }
+
+        Self { entity }
+    }
+
+    // read a map from disk. both the standard and the Valve 220 format are supported.
+    pub fn new_from_file(path: &str) -> Result<Self, String> {
+        let data =
+            std::fs::read_to_string(path).map_err(|e| format!("Quake::new_from_file(): {e}"))?;
+
+        Self::new_from_text(&data)
+    }
+
+    // read a map from text.
+    pub fn new_from_text(text: &str) -> Result<Self, String> {
+        let mut reader = QuakeReader::new(text);
+        let mut entity = Vec::new();
+
+        while reader.peek().is_some() {
+            entity.push(QuakeEntity::parse(&mut reader)?);
+        }
+
+        Ok(Self { entity })
+    }
+
+    // convert the map to a world, matching every entity against the entity list from Lua.
+    // returns the world, alongside a warning for everything in the map which could not be converted.
+    pub fn to_world(
+        &self,
+        game: &Game,
+        asset: &Asset,
+        meta: &[EntityMeta],
+    ) -> (World, Vec<String>) {
+        let mut world = World {
+            brush: Vec::new(),
+            entity: Vec::new(),
+            selection: Default::default(),
+        };
+        let mut skip = 0;
+        let mut flat = Vec::new();
+        let mut data = Vec::new();
+
+        for entity in &self.entity {
+            let mut center = Vector3::zero();
+            let mut count = 0.0;
+
+            // the world only has a single list of brushes, and no data of its own, so the brushes of a brush entity are
+            // added to it, and the data of the worldspawn is lost. the exporter writes the map version again on its own.
+            match entity.get("classname") {
+                Some("worldspawn") => data.extend(
+                    entity
+                        .data
+                        .iter()
+                        .map(|(key, _)| key.as_str())
+                        .filter(|key| !matches!(*key, "classname" | "mapversion"))
+                        .map(str::to_string),
+                ),
+                Some(class) if !entity.brush.is_empty() => flat.push(class.to_string()),
+                _ => {}
+            }
+
+            for brush in &entity.brush {
+                if let Some(brush) = brush.to_brush(game, asset) {
+                    for vertex in &brush.vertex {
+                        center += vertex.point;
+                        count += 1.0;
+                    }
+
+                    world.brush.push(brush);
+                } else {
+                    skip += 1;
+                }
+            }
+
+            // brush entities have no origin, so use the center of their brushes instead.
+            if count > 0.0 {
+                center /= count;
+            }
+
+            if let Some(entity) = entity.to_entity(meta, center) {
+                world.entity.push(entity);
+            }
+        }
+
+        let mut warning = Vec::new();
+
+        if skip > 0 {
+            warning.push(format!(
+                "{skip} brush(es) could not be imported, as they do not enclose a volume."
+            ));
+        }
+
+        if !flat.is_empty() {
+            warning.push(format!(
+                "{} brush entit(ies) were imported as point entities, with their brushes moved into the world: {}.",
+                flat.len(),
+                flat.join(", ")
+            ));
+        }
+
+        if !data.is_empty() {
+            warning.push(format!(
+                "The worldspawn data could not be imported: {}.",
+                data.join(", ")
+            ));
+        }
+
+        (world, warning)
+    }
+
+    // write the map to disk.
+    pub fn save_file(&self, path: &str) -> Result<(), String> {
+        std::fs::write(path, self.to_string()).map_err(|e| format!("Quake::save_file(): {e}"))
+    }
+
+    // convert a point from Mallet space (Y-up) to Quake space (Z-up).
+    pub fn point_to_quake(point: Vector3) -> Vector3 {
+        Vector3::new(point.x, -point.z, point.y) * Self::SCALE
+    }
+
+    // convert a point from Quake space (Z-up) to Mallet space (Y-up).
+    pub fn point_from_quake(point: Vector3) -> Vector3 {
+        Vector3::new(point.x, point.z, -point.y) / Self::SCALE
+    }
+
+    // get the Quake name of a texture, which is the path relative to the game, without the extension.
+    pub fn texture_name(game: &Game, path: &str) -> String {
+        let name = path.strip_prefix(&game.path).unwrap_or(path);
+        let name = name.trim_start_matches(['/', '\\']);
+        let name = std::path::Path::new(name).with_extension("");
+
+        name.to_string_lossy().replace('\\', "/")
+    }
+
+    // round a number for writing, also getting rid of any negative zero.
+    fn round(value: f32) -> f32 {
+        (value * 100000.0).round() / 100000.0 + 0.0
+    }
+}
+
+impl fmt::Display for Quake {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "// Format: Valve")?;
+
+        for (i, entity) in self.entity.iter().enumerate() {
+            writeln!(f, "// entity {i}")?;
+            write!(f, "{entity}")?;
+        }
+
+        Ok(())
+    }
+}
+
+//================================================================
+
+pub struct QuakeEntity {
+    pub data: Vec<(String, String)>,
+    pub brush: Vec<QuakeBrush>,
+}
+
+impl QuakeEntity {
+    pub fn new(class: &str) -> Self {
+        Self {
+            data: vec![("classname".to_string(), class.to_string())],
+            brush: Vec::new(),
+        }
+    }
+
+    pub fn new_from_entity(entity: &Entity) -> Self {
+        let mut result = Self::new(&entity.meta.name);
+
+        let origin = Quake::point_to_quake(entity.position);
+
+        // Mallet rotates around X, Y, Z, which become roll, yaw and (inverted) pitch in Quake space.
+        let angle = Vector3::new(-entity.rotation.z, entity.rotation.y, entity.rotation.x);
+
+        result.data.push((
+            "origin".to_string(),
+            format!(
+                "{} {} {}",
+                Quake::round(origin.x),
+                Quake::round(origin.y),
+                Quake::round(origin.z)
+            ),
+        ));
+        result.data.push((
+            "angles".to_string(),
+            format!(
+                "{} {} {}",
+                Quake::round(angle.x),
+                Quake::round(angle.y),
+                Quake::round(angle.z)
+            ),
+        ));
+
+        // sort the entity data, so that the output does not change between exports.
+        let mut data: Vec<_> = entity.meta.data.iter().collect();
+        data.sort_by(|a, b| a.0.cmp(b.0));
+
+        for (key, value) in data {
+            let value = match &value.kind {
+                serde_json::Value::Bool(value) => (if *value { "1" } else { "0" }).to_string(),
+                serde_json::Value::String(value) => value.clone(),
+                value => value.to_string(),
+            };
+
+            // the map format has no escape sequence for quotes.
+            result.data.push((key.clone(), value.replace('"', "'")));
+        }
+
+        result
+    }
+}
+
+impl QuakeEntity {
+    fn parse(reader: &mut QuakeReader) -> Result<Self, String> {
+        let mut result = Self {
+            data: Vec::new(),
+            brush: Vec::new(),
+        };
+
+        reader.expect("{")?;
+
+        loop {
+            match reader.next()? {
+                "}" => break,
+                "{" => {
+                    if let Some(brush) = QuakeBrush::parse(reader)? {
+                        result.brush.push(brush);
+                    }
+                }
+                key => {
+                    let key = key.to_string();
+                    let value = reader.next()?.to_string();
+                    result.data.push((key, value));
+                }
+            }
+        }
+
+        Ok(result)
+    }
+
+    // get the value of a key.
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.data
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v.as_str())
+    }
+
+    // convert the entity to a Mallet entity. entities that are not known to the game are kept as generic entities.
+    pub fn to_entity(&self, meta: &[EntityMeta], center: Vector3) -> Option<Entity> {
+        let class = self.get("classname")?;
+
+        if class == "worldspawn" {
+            return None;
+        }
+
+        let mut result = meta
+            .iter()
+            .find(|m| m.name == class)
+            .cloned()
+            .unwrap_or(EntityMeta {
+                name: class.to_string(),
+                info: String::new(),
+                data: HashMap::new(),
+                shape: BoundingBox::new(Vector3::one() * -0.5, Vector3::one() * 0.5),
+                call: None,
+            });
+
+        for (key, value) in &self.data {
+            if matches!(key.as_str(), "classname" | "origin" | "angles" | "angle") {
+                continue;
+            }
+
+            // keep the type of the entity data as declared from Lua, or store it as text if it is unknown.
+            if let Some(data) = result.data.get_mut(key) {
+                data.kind = match data.kind {
+                    serde_json::Value::Bool(_) => {
+                        serde_json::Value::Bool(value == "1" || value.eq_ignore_ascii_case("true"))
+                    }
+                    serde_json::Value::Number(_) => value
+                        .parse::<f64>()
+                        .ok()
+                        .and_then(serde_json::Number::from_f64)
+                        .map(serde_json::Value::Number)
+                        .unwrap_or(serde_json::Value::String(value.clone())),
+                    _ => serde_json::Value::String(value.clone()),
+                };
+            } else {
+                result.data.insert(
+                    key.clone(),
+                    EntityData {
+                        info: key.clone(),
+                        kind: serde_json::Value::String(value.clone()),
+                    },
+                );
+            }
+        }
+
+        let mut entity = Entity::new_from_lua(result);
+
+        entity.position = self
+            .get("origin")
+            .and_then(QuakeReader::vector)
+            .map(Quake::point_from_quake)
+            .unwrap_or(center);
+
+        // pitch, yaw and roll in Quake space become (inverted) Z, Y and X in Mallet space.
+        if let Some(angle) = self.get("angles").and_then(QuakeReader::vector) {
+            entity.rotation = Vector3::new(angle.z, angle.y, -angle.x);
+        } else if let Some(angle) = self.get("angle").and_then(|v| v.parse::<f32>().ok()) {
+            entity.rotation = Vector3::new(0.0, angle, 0.0);
+        }
+
+        Some(entity)
+    }
+}
+
+impl fmt::Display for QuakeEntity {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "{{")?;
+
+        for (key, value) in &self.data {
+            writeln!(f, "\"{key}\" \"{value}\"")?;
+        }
+
+        for (i, brush) in self.brush.iter().enumerate() {
+            writeln!(f, "// brush {i}")?;
+            write!(f, "{brush}")?;
+        }
+
+        writeln!(f, "}}")
+    }
+}
+
+//================================================================
+
+pub struct QuakeBrush {
+    pub face: Vec<QuakeFace>,
+}
+
+impl QuakeBrush {
+    pub fn new_from_brush(brush: &Brush, game: &Game, asset: &Asset) -> Self {
+        let face = brush
+            .face
+            .iter()
+            .map(|f| {
+                let texture = {
+                    if let Some(texture) = &f.texture {
+                        Quake::texture_name(game, texture)
+                    } else {
+                        Quake::TEXTURE_DEFAULT.to_string()
+                    }
+                };
+
+                QuakeFace::new_from_face(brush, f, texture, asset.texture_shape(&f.texture))
+            })
+            .collect();
+
+        Self { face }
+    }
+}
+
+impl QuakeBrush {
+    // parse a brush. returns None for anything which is not a plane brush, such as a patch.
+    fn parse(reader: &mut QuakeReader) -> Result<Option<Self>, String> {
+        if reader.peek() != Some("(") {
+            // skip the whole block.
+            let mut depth = 1;
+
+            while depth > 0 {
+                match reader.next()? {
+                    "{" => depth += 1,
+                    "}" => depth -= 1,
+                    _ => {}
+                }
+            }
+
+            return Ok(None);
+        }
+
+        let mut face = Vec::new();
+
+        while reader.peek() != Some("}") {
+            face.push(QuakeFace::parse(reader)?);
+        }
+
+        reader.expect("}")?;
+
+        Ok(Some(Self { face }))
+    }
+
+    // convert the brush to a Mallet brush, by intersecting the plane of every face.
+    pub fn to_brush(&self, game: &Game, asset: &Asset) -> Option<Brush> {
+        let mut plane = Vec::new();
+        let mut source = Vec::new();
+
+        for face in &self.face {
+            if let Some(p) = face.plane() {
+                plane.push(p);
+                source.push(face);
+            }
+        }
+
+        let polyhedron = Polyhedron::new_from_plane(&plane)?;
+
+        let face = polyhedron
+            .face
+            .iter()
+            .map(|(i, index)| source[*i].to_face(index, &polyhedron.vertex, game, asset))
+            .collect();
+
+        Some(Brush::new(&polyhedron.vertex, face))
+    }
+}
+
+impl fmt::Display for QuakeBrush {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "{{")?;
+
+        for face in &self.face {
+            writeln!(f, "{face}")?;
+        }
+
+        writeln!(f, "}}")
+    }
+}
+
+//================================================================
+
+pub struct QuakeFace {
+    pub point: [Vector3; 3],
+    pub texture: String,
+    pub axis_u: Vector3,
+    pub axis_v: Vector3,
+    pub shift: Vector2,
+    pub angle: f32,
+    pub scale: Vector2,
+}
+
+impl QuakeFace {
+    // convert a face of a brush, with the name of its texture and the size of it, in pixels.
+    pub fn new_from_face(brush: &Brush, f: &Face, texture: String, shape: Vector2) -> Self {
+        let point: Vec<Vector3> = f
+            .index
+            .iter()
+            .map(|i| Quake::point_to_quake(brush.vertex[*i].point))
+            .collect();
+        // pick the three consecutive corners with the biggest area, to get the most precise plane.
+        let mut pick = 0;
+        let mut area = 0.0;
+
+        for i in 0..point.len() {
+            let a = point[i];
+            let b = point[(i + 1) % point.len()];
+            let c = point[(i + 2) % point.len()];
+            let work = (b - a).cross(c - a).length();
+
+            if work > area {
+                area = work;
+                pick = i;
+            }
+        }
+
+        let pick = [pick, (pick + 1) % point.len(), (pick + 2) % point.len()];
+
+        let uv = f.uv(&brush.point(f), shape);
+
+        let corner = [point[pick[0]], point[pick[1]], point[pick[2]]];
+        let (axis_u, shift_u, scale_u) = Self::texture_axis(
+            corner,
+            [uv[pick[0]].x, uv[pick[1]].x, uv[pick[2]].x],
+            shape.x,
+        );
+        let (axis_v, shift_v, scale_v) = Self::texture_axis(
+            corner,
+            [uv[pick[0]].y, uv[pick[1]].y, uv[pick[2]].y],
+            shape.y,
+        );
+
+        Self {
+            // Quake wants the plane points in clock-wise order, as seen from outside of the brush.
+            point: [corner[2], corner[1], corner[0]],
+            texture,
+            axis_u,
+            axis_v,
+            shift: Vector2::new(shift_u, shift_v),
+            angle: 0.0,
+            scale: Vector2::new(scale_u, scale_v),
+        }
+    }
+
+    fn parse(reader: &mut QuakeReader) -> Result<Self, String> {
+        let mut point = [Vector3::zero(); 3];
+
+        for p in &mut point {
+            reader.expect("(")?;
+            *p = Vector3::new(reader.number()?, reader.number()?, reader.number()?);
+            reader.expect(")")?;
+        }
+
+        let texture = reader.next()?.to_string();
+
+        let mut result = Self {
+            point,
+            texture,
+            axis_u: Vector3::zero(),
+            axis_v: Vector3::zero(),
+            shift: Vector2::zero(),
+            angle: 0.0,
+            scale: Vector2::one(),
+        };
+
+        if reader.peek() == Some("[") {
+            // Valve 220 format: [ ux uy uz shift ] [ vx vy vz shift ] angle scale scale.
+            reader.expect("[")?;
+            result.axis_u = Vector3::new(reader.number()?, reader.number()?, reader.number()?);
+            result.shift.x = reader.number()?;
+            reader.expect("]")?;
+            reader.expect("[")?;
+            result.axis_v = Vector3::new(reader.number()?, reader.number()?, reader.number()?);
+            result.shift.y = reader.number()?;
+            reader.expect("]")?;
+            result.angle = reader.number()?;
+            result.scale = Vector2::new(reader.number()?, reader.number()?);
+        } else {
+            // standard format: shift shift angle scale scale. the axis is implied by the plane.
+            result.shift = Vector2::new(reader.number()?, reader.number()?);
+            result.angle = reader.number()?;
+            result.scale = Vector2::new(reader.number()?, reader.number()?);
+
+            let normal = (point[0] - point[1]).cross(point[2] - point[1]);
+            let (axis_u, axis_v) = Self::texture_axis_standard(normal, result.angle);
+
+            result.axis_u = axis_u;
+            result.axis_v = axis_v;
+            result.angle = 0.0;
+        }
+
+        if result.scale.x == 0.0 {
+            result.scale.x = 1.0;
+        }
+        if result.scale.y == 0.0 {
+            result.scale.y = 1.0;
+        }
+
+        // skip any extra data, such as the surface flags from Quake 2 and 3.
+        while !matches!(reader.peek(), Some("(") | Some("}") | None) {
+            reader.next()?;
+        }
+
+        Ok(result)
+    }
+
+    // get the plane of the face, in Mallet space.
+    pub fn plane(&self) -> Option<Plane> {
+        // the Quake plane normal is (p0 - p1) x (p2 - p1), which points outside of the brush.
+        Plane::new_from_point(
+            Quake::point_from_quake(self.point[1]),
+            Quake::point_from_quake(self.point[0]),
+            Quake::point_from_quake(self.point[2]),
+        )
+    }
+
+    // convert the face to a Mallet face, given its corner indices and the vertex list of the brush.
+    pub fn to_face(&self, index: &[usize], vertex: &[Vector3], game: &Game, asset: &Asset) -> Face {
+        // find the loaded texture with the same name, or keep the name as-is if there is none.
+        let path = asset
+            .outer
+            .texture
+            .keys()
+            .find(|path| Quake::texture_name(game, path) == self.texture);
+
+        let shape = path
+            .and_then(|path| asset.outer.texture.get(path))
+            .unwrap_or(&asset.inner.default);
+        let shape = Vector2::new(shape.width as f32, shape.height as f32);
+
+        let mut result = self.to_face_shape(index, vertex, shape);
+
+        result.texture = {
+            if let Some(path) = path {
+                Some(path.clone())
+            } else if self.texture == Quake::TEXTURE_DEFAULT {
+                None
+            } else {
+                Some(self.texture.clone())
+            }
+        };
+
+        result
+    }
+
+    // convert the face to a Mallet face, for a texture of the given size, in pixels. the texture itself is left unset.
+    pub fn to_face_shape(&self, index: &[usize], vertex: &[Vector3], shape: Vector2) -> Face {
+        let mut result = Face::new(index.to_vec());
+        let mut best = f32::MAX;
+
+        // Mallet fits the texture along the first edge of the face, before it is shifted and scaled.
+        // try every corner as the first, and keep whichever fits best, preferring a positive scale.
+        for r in 0..index.len() {
+            let work_index: Vec<usize> = (0..index.len())
+                .map(|i| index[(i + r) % index.len()])
+                .collect();
+            let point: Vec<Vector3> = work_index.iter().map(|i| vertex[*i]).collect();
+            let base = Face::uv_base(&point);
+
+            // texture coordinate of each corner, where 1.0 is the size of the texture.
+            let uv: Vec<Vector2> = point
+                .iter()
+                .map(|p| {
+                    let p = Quake::point_to_quake(*p);
+
+                    Vector2::new(
+                        (p.dot(self.axis_u) / self.scale.x + self.shift.x) / shape.x,
+                        (p.dot(self.axis_v) / self.scale.y + self.shift.y) / shape.y,
+                    )
+                })
+                .collect();
+
+            let (Some((shift_x, scale_x, error_x)), Some((shift_y, scale_y, error_y))) = (
+                Self::fit(
+                    &base.iter().map(|v| v.x).collect::<Vec<_>>(),
+                    &uv.iter().map(|v| v.x).collect::<Vec<_>>(),
+                ),
+                Self::fit(
+                    &base.iter().map(|v| v.y).collect::<Vec<_>>(),
+                    &uv.iter().map(|v| v.y).collect::<Vec<_>>(),
+                ),
+            ) else {
+                continue;
+            };
+
+            let mut work = error_x + error_y;
+
+            if scale_x < 0.0 || scale_y < 0.0 {
+                work += Plane::EPSILON;
+            }
+
+            if work < best {
+                best = work;
+                result.index = work_index;
+                result.shift = Vector2::new(shift_x, shift_y);
+                result.scale = Vector2::new(scale_x, scale_y);
+            }
+        }
+
+        result
+    }
+
+    // fit y = scale * (shift + x) over every value, with least squares. returns the shift, the scale, and the squared error.
+    fn fit(x: &[f32], y: &[f32]) -> Option<(f32, f32, f32)> {
+        let count = x.len() as f32;
+        let mean_x = x.iter().sum::<f32>() / count;
+        let mean_y = y.iter().sum::<f32>() / count;
+        let mut variance = 0.0;
+        let mut covariance = 0.0;
+
+        for (a, b) in x.iter().zip(y) {
+            variance += (a - mean_x) * (a - mean_x);
+            covariance += (a - mean_x) * (b - mean_y);
+        }
+
+        if variance <= f32::EPSILON {
+            return None;
+        }
+
+        let scale = covariance / variance;
+
+        if scale.abs() <= f32::EPSILON {
+            return None;
+        }
+
+        let shift = (mean_y - scale * mean_x) / scale;
+        let error = x
+            .iter()
+            .zip(y)
+            .map(|(a, b)| (scale * (shift + a) - b).powi(2))
+            .sum();
+
+        Some((shift, scale, error))
+    }
+
+    // get the texture axis of a standard format face, which is picked from the axis closest to the face normal.
+    #[rustfmt::skip]
+    fn texture_axis_standard(normal: Vector3, angle: f32) -> (Vector3, Vector3) {
+        const AXIS: [[[f32; 3]; 3]; 6] = [
+            [[ 0.0,  0.0,  1.0], [1.0, 0.0,  0.0], [0.0, -1.0,  0.0]],
+            [[ 0.0,  0.0, -1.0], [1.0, 0.0,  0.0], [0.0, -1.0,  0.0]],
+            [[ 1.0,  0.0,  0.0], [0.0, 1.0,  0.0], [0.0,  0.0, -1.0]],
+            [[-1.0,  0.0,  0.0], [0.0, 1.0,  0.0], [0.0,  0.0, -1.0]],
+            [[ 0.0,  1.0,  0.0], [1.0, 0.0,  0.0], [0.0,  0.0, -1.0]],
+            [[ 0.0, -1.0,  0.0], [1.0, 0.0,  0.0], [0.0,  0.0, -1.0]],
+        ];
+
+        let mut pick = 0;
+        let mut best = f32::MIN;
+
+        for (i, axis) in AXIS.iter().enumerate() {
+            let work = normal.dot(Vector3::new(axis[0][0], axis[0][1], axis[0][2]));
+
+            if work > best {
+                best = work;
+                pick = i;
+            }
+        }
+
+        let mut axis = [AXIS[pick][1], AXIS[pick][2]];
+
+        // rotate both axis around the plane they lie on.
+        let s = axis[0].iter().position(|v| *v != 0.0).unwrap_or(2);
+        let t = axis[1].iter().position(|v| *v != 0.0).unwrap_or(2);
+        let (sin, cos) = (angle * DEG2RAD as f32).sin_cos();
+
+        for a in &mut axis {
+            let s_value = cos * a[s] - sin * a[t];
+            let t_value = sin * a[s] + cos * a[t];
+            a[s] = s_value;
+            a[t] = t_value;
+        }
+
+        (
+            Vector3::new(axis[0][0], axis[0][1], axis[0][2]),
+            Vector3::new(axis[1][0], axis[1][1], axis[1][2]),
+        )
+    }
+
+    // find the Valve 220 texture axis, shift and scale for a plane, given three points on it and their texture coordinate.
+    // returns the axis, then the shift and the scale in pixels.
+    pub fn texture_axis(point: [Vector3; 3], value: [f32; 3], shape: f32) -> (Vector3, f32, f32) {
+        // the texture coordinate is an affine function of the point: value = dot(point, a) + b.
+        // solve for the "a" vector, which lies on the plane, from the two edges of the triangle.
+        let e_1 = point[1] - point[0];
+        let e_2 = point[2] - point[0];
+        let d_1 = value[1] - value[0];
+        let d_2 = value[2] - value[0];
+
+        let g_11 = e_1.dot(e_1);
+        let g_12 = e_1.dot(e_2);
+        let g_22 = e_2.dot(e_2);
+        let det = g_11 * g_22 - g_12 * g_12;
+
+        if det.abs() > f32::EPSILON {
+            let x = (d_1 * g_22 - d_2 * g_12) / det;
+            let y = (d_2 * g_11 - d_1 * g_12) / det;
+            let a = e_1 * x + e_2 * y;
+            let b = value[0] - point[0].dot(a);
+            let length = a.length();
+
+            if length > f32::EPSILON {
+                return (a / length, b * shape, 1.0 / (length * shape));
+            }
+        }
+
+        // degenerate face or texture scale, use the first edge instead.
+        (e_1.normalized(), 0.0, 1.0)
+    }
+}
+
+impl fmt::Display for QuakeFace {
+    #[rustfmt::skip]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for point in &self.point {
+            write!(f, "( {} {} {} ) ", Quake::round(point.x), Quake::round(point.y), Quake::round(point.z))?;
+        }
+
+        write!(
+            f,
+            "{} [ {} {} {} {} ] [ {} {} {} {} ] {} {} {}",
+            self.texture,
+            Quake::round(self.axis_u.x), Quake::round(self.axis_u.y), Quake::round(self.axis_u.z), Quake::round(self.shift.x),
+            Quake::round(self.axis_v.x), Quake::round(self.axis_v.y), Quake::round(self.axis_v.z), Quake::round(self.shift.y),
+            Quake::round(self.angle),
+            Quake::round(self.scale.x),
+            Quake::round(self.scale.y),
+        )
+    }
+}
+
+//================================================================
+
+// a token reader for the map format.
+struct QuakeReader<'a> {
+    token: Vec<&'a str>,
+    index: usize,
+}
+
+impl<'a> QuakeReader<'a> {
+    fn new(text: &'a str) -> Self {
+        let mut token = Vec::new();
+        let mut rest = text;
+
+        loop {
+            rest = rest.trim_start();
+
+            if rest.is_empty() {
+                break;
+            }
+
+            if rest.starts_with("//") {
+                // comment, skip until the end of the line.
+                rest = rest.find('\n').map(|i| &rest[i..]).unwrap_or("");
+            } else if let Some(quote) = rest.strip_prefix('"') {
+                // quoted text, which can hold white-space.
+                let end = quote.find('"').unwrap_or(quote.len());
+                token.push(&quote[..end]);
+                rest = quote.get(end + 1..).unwrap_or("");
+            } else if rest.starts_with(['{', '}', '(', ')', '[', ']']) {
+                token.push(&rest[..1]);
+                rest = &rest[1..];
+            } else {
+                let end = rest
+                    .find(|c: char| c.is_whitespace() || "{}()[]\"".contains(c))
+                    .unwrap_or(rest.len());
+                token.push(&rest[..end]);
+                rest = &rest[end..];
+            }
+        }
+
+        Self { token, index: 0 }
+    }
+
+    fn peek(&self) -> Option<&'a str> {
+        self.token.get(self.index).copied()
+    }
+
+    fn next(&mut self) -> Result<&'a str, String> {
+        let token = self
+            .peek()
+            .ok_or("QuakeReader::next(): Unexpected end of file.".to_string())?;
+        self.index += 1;
+
+        Ok(token)
+    }
+
+    fn expect(&mut self, value: &str) -> Result<(), String> {
+        let token = self.next()?;
+
+        if token == value {
+            Ok(())
+        } else {
+            Err(format!(
+                "QuakeReader::expect(): Expected \"{value}\", found \"{token}\" (token {}).",
+                self.index
+            ))
+        }
+    }
+
+    fn number(&mut self) -> Result<f32, String> {
+        let token = self.next()?;
+
+        token.parse().map_err(|_| {
+            format!(
+                "QuakeReader::number(): Expected a number, found \"{token}\" (token {}).",
+                self.index
+            )
+        })
+    }
+
+    // parse a "x y z" vector from an entity value.
+    fn vector(value: &str) -> Option<Vector3> {
+        let value: Vec<f32> = value
+            .split_whitespace()
+            .filter_map(|v| v.parse().ok())
+            .collect();
+
+        match value[..] {
+            [x, y, z] => Some(Vector3::new(x, y, z)),
+            _ => None,
+        }
+    }
+}
+
+//================================================================
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    // the corners the importer builds must be where the exporter wrote them, with the same texture coordinate.
+    #[test]
+    fn brush_round_trip() {
+        let shape = Vector2::new(64.0, 32.0);
+        let mut brush = Brush::default();
+
+        for vertex in &mut brush.vertex {
+            vertex.point.x *= 2.0;
+        }
+
+        brush.set_plane();
+
+        for (i, face) in brush.face.iter_mut().enumerate() {
+            face.shift = Vector2::new(0.25 * i as f32, -0.5);
+            face.scale = Vector2::new(2.0, 0.5);
+        }
+
+        let mut spawn = QuakeEntity::new("worldspawn");
+        spawn.brush.push(QuakeBrush {
+            face: brush
+                .face
+                .iter()
+                .map(|f| QuakeFace::new_from_face(&brush, f, "wall".to_string(), shape))
+                .collect(),
+        });
+
+        let text = Quake {
+            entity: vec![spawn],
+        }
+        .to_string();
+        let quake = Quake::new_from_text(&text).unwrap();
+        let source = &quake.entity[0].brush[0];
+
+        assert_eq!(source.face.len(), brush.face.len());
+        assert!(source.face.iter().all(|f| f.texture == "wall"));
+
+        let plane: Vec<Plane> = source.face.iter().map(|f| f.plane().unwrap()).collect();
+        let polyhedron = Polyhedron::new_from_plane(&plane).unwrap();
+
+        assert_eq!(polyhedron.vertex.len(), brush.vertex.len());
+        assert_eq!(polyhedron.face.len(), brush.face.len());
+
+        for (i, index) in &polyhedron.face {
+            let face = source.face[*i].to_face_shape(index, &polyhedron.vertex, shape);
+            let point: Vec<Vector3> = face.index.iter().map(|j| polyhedron.vertex[*j]).collect();
+            let uv = face.uv(&point, shape);
+
+            let before = &brush.face[*i];
+            let before_point = brush.point(before);
+            let before_uv = before.uv(&before_point, shape);
+
+            for (p, uv) in point.iter().zip(uv) {
+                let k = before_point
+                    .iter()
+                    .position(|b| (*b - *p).length() < 0.001)
+                    .unwrap();
+
+                assert!((before_uv[k] - uv).length() < 0.001);
+            }
+        }
+    }
+
+    // every key and value must be read back as it was written, in order.
+    #[test]
+    fn entity_round_trip() {
+        let mut entity = QuakeEntity::new("light");
+        entity
+            .data
+            .push(("origin".to_string(), "32 -64 16".to_string()));
+        entity.data.push((
+            "message".to_string(),
+            "a value { with } // symbols".to_string(),
+        ));
+
+        let data = entity.data.clone();
+        let text = Quake {
+            entity: vec![QuakeEntity::new("worldspawn"), entity],
+        }
+        .to_string();
+        let quake = Quake::new_from_text(&text).unwrap();
+
+        assert_eq!(quake.entity.len(), 2);
+        assert_eq!(quake.entity[0].get("classname"), Some("worldspawn"));
+        assert_eq!(quake.entity[1].data, data);
+        assert!(quake.entity[1].brush.is_empty());
+    }
+
+    // a standard format face has no texture axis, so it must be picked from the normal of the plane.
+    #[test]
+    fn face_standard() {
+        let text =
+            "{ \"classname\" \"worldspawn\" { ( 0 0 0 ) ( 0 64 0 ) ( 64 0 0 ) wall 0 0 0 1 1 } }";
+        let quake = Quake::new_from_text(text).unwrap();
+        let face = &quake.entity[0].brush[0].face[0];
+
+        assert_eq!(face.axis_u, Vector3::new(1.0, 0.0, 0.0));
+        assert_eq!(face.axis_v, Vector3::new(0.0, -1.0, 0.0));
+    }
+
+    // a block which is not a plane brush, such as a patch, must be skipped over.
+    #[test]
+    fn patch_skip() {
+        let text = "{ \"classname\" \"worldspawn\" { patchDef2 { wall ( 3 3 0 0 0 ) ( ( ( 0 0 0 0 0 ) ) ) } } }";
+        let quake = Quake::new_from_text(text).unwrap();
+
+        assert!(quake.entity[0].brush.is_empty());
+    }
+}
@@ -0,0 +1,446 @@
+/*
+* BSD Zero Clause License
+*
+* Copyright (c) 2025 sockentrocken
+*
+* Permission to use, copy, modify, and/or distribute this software for any
+* purpose with or without fee is hereby granted.
+*
+* THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHOR DISCLAIMS ALL WARRANTIES WITH
+* REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF MERCHANTABILITY
+* AND FITNESS. IN NO EVENT SHALL THE AUTHOR BE LIABLE FOR ANY SPECIAL, DIRECT,
+* INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES WHATSOEVER RESULTING FROM
+* LOSS OF USE, DATA OR PROFITS, WHETHER IN AN ACTION OF CONTRACT, NEGLIGENCE OR
+* OTHER TORTIOUS ACTION, ARISING OUT OF OR IN CONNECTION WITH THE USE OR
+* PERFORMANCE OF THIS SOFTWARE.
+*/
+
+use crate::editor::*;
+
+//================================================================
+
+use std::collections::BTreeSet;
+
+//================================================================
+
+// a part of the world that may be selected. a vertex, edge or face is given by the index of its brush, and its own index
+// in that brush. an edge is a pair of vertex indices, lowest first.
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub enum SelectionItem {
+    Brush(usize),
+    Entity(usize),
+    Vertex(usize, usize),
+    Edge(usize, (usize, usize)),
+    Face(usize, usize),
+}
+
+//================================================================
+
+// the kind of part a selection command works on: every brush and entity, or a part of every selected brush.
+#[derive(Copy, Clone, PartialEq)]
+pub enum SelectionKind {
+    Object,
+    Vertex,
+    Edge,
+    Face,
+}
+
+impl SelectionKind {
+    pub fn new(widget: &Widget) -> Self {
+        match widget {
+            Widget::Vertex => Self::Vertex,
+            Widget::Edge => Self::Edge,
+            Widget::Face => Self::Face,
+            _ => Self::Object,
+        }
+    }
+}
+
+//================================================================
+
+// the selection of the world. it is not part of the map data, but it is part of every history step, so that an undo
+// brings back what was selected alongside it.
+#[derive(Clone, Default, PartialEq)]
+pub struct Selection {
+    brush: BTreeSet<usize>,
+    entity: BTreeSet<usize>,
+    vertex: BTreeSet<(usize, usize)>,
+    edge: BTreeSet<(usize, (usize, usize))>,
+    face: BTreeSet<(usize, usize)>,
+}
+
+impl Selection {
+    // check if a part is selected.
+    pub fn has(&self, item: SelectionItem) -> bool {
+        match item {
+            SelectionItem::Brush(i) => self.brush.contains(&i),
+            SelectionItem::Entity(i) => self.entity.contains(&i),
+            SelectionItem::Vertex(i, j) => self.vertex.contains(&(i, j)),
+            SelectionItem::Edge(i, (a, b)) => self.edge.contains(&(i, (a.min(b), a.max(b)))),
+            SelectionItem::Face(i, j) => self.face.contains(&(i, j)),
+        }
+    }
+
+    // select a part. selecting a vertex, edge or face also selects its brush, so that it may be edited.
+    pub fn add(&mut self, item: SelectionItem) {
+        match item {
+            SelectionItem::Brush(i) => {
+                self.brush.insert(i);
+            }
+            SelectionItem::Entity(i) => {
+                self.entity.insert(i);
+            }
+            SelectionItem::Vertex(i, j) => {
+                self.brush.insert(i);
+                self.vertex.insert((i, j));
+            }
+            SelectionItem::Edge(i, (a, b)) => {
+                self.brush.insert(i);
+                self.edge.insert((i, (a.min(b), a.max(b))));
+            }
+            SelectionItem::Face(i, j) => {
+                self.brush.insert(i);
+                self.face.insert((i, j));
+            }
+        }
+    }
+
+    // deselect a part. deselecting a brush also deselects every part of it.
+    pub fn remove(&mut self, item: SelectionItem) {
+        match item {
+            SelectionItem::Brush(i) => {
+                self.brush.remove(&i);
+                self.remove_part(i);
+            }
+            SelectionItem::Entity(i) => {
+                self.entity.remove(&i);
+            }
+            SelectionItem::Vertex(i, j) => {
+                self.vertex.remove(&(i, j));
+            }
+            SelectionItem::Edge(i, (a, b)) => {
+                self.edge.remove(&(i, (a.min(b), a.max(b))));
+            }
+            SelectionItem::Face(i, j) => {
+                self.face.remove(&(i, j));
+            }
+        }
+    }
+
+    // select a part if it is not selected, or deselect it otherwise.
+    pub fn toggle(&mut self, item: SelectionItem) {
+        self.set(item, !self.has(item));
+    }
+
+    // select or deselect a part.
+    pub fn set(&mut self, item: SelectionItem, value: bool) {
+        if value {
+            self.add(item);
+        } else {
+            self.remove(item);
+        }
+    }
+
+    // deselect everything.
+    pub fn select_none(&mut self) {
+        *self = Self::default();
+    }
+
+    // select every part of a kind.
+    pub fn select_all(&mut self, world: &World, kind: SelectionKind) {
+        for item in self.list(world, kind) {
+            self.add(item);
+        }
+    }
+
+    // select every part of a kind that is not selected, and deselect every one that is.
+    pub fn invert(&mut self, world: &World, kind: SelectionKind) {
+        for item in self.list(world, kind) {
+            self.toggle(item);
+        }
+    }
+
+    // get every part of a kind: every brush and entity, or every vertex, edge or face of every selected brush.
+    fn list(&self, world: &World, kind: SelectionKind) -> Vec<SelectionItem> {
+        let mut list = Vec::new();
+
+        if kind == SelectionKind::Object {
+            list.extend((0..world.brush.len()).map(SelectionItem::Brush));
+            list.extend((0..world.entity.len()).map(SelectionItem::Entity));
+
+            return list;
+        }
+
+        for i in self.brush.iter().copied() {
+            let Some(brush) = world.brush.get(i) else {
+                continue;
+            };
+
+            match kind {
+                SelectionKind::Vertex => {
+                    list.extend((0..brush.vertex.len()).map(|j| SelectionItem::Vertex(i, j)))
+                }
+                SelectionKind::Edge => list.extend(
+                    brush
+                        .edge_list()
+                        .into_iter()
+                        .map(|e| SelectionItem::Edge(i, e)),
+                ),
+                SelectionKind::Face => {
+                    list.extend((0..brush.face.len()).map(|j| SelectionItem::Face(i, j)))
+                }
+                SelectionKind::Object => {}
+            }
+        }
+
+        list
+    }
+
+    // check if nothing is selected.
+    pub fn is_empty(&self) -> bool {
+        self.brush.is_empty() && self.entity.is_empty()
+    }
+
+    // check if a brush is selected.
+    pub fn brush(&self, i: usize) -> bool {
+        self.brush.contains(&i)
+    }
+
+    // check if an entity is selected.
+    pub fn entity(&self, i: usize) -> bool {
+        self.entity.contains(&i)
+    }
+
+    // check if a vertex of a brush is selected.
+    pub fn vertex(&self, i: usize, j: usize) -> bool {
+        self.vertex.contains(&(i, j))
+    }
+
+    // check if an edge of a brush is selected.
+    pub fn edge(&self, i: usize, edge: (usize, usize)) -> bool {
+        self.has(SelectionItem::Edge(i, edge))
+    }
+
+    // check if a face of a brush is selected.
+    pub fn face(&self, i: usize, j: usize) -> bool {
+        self.face.contains(&(i, j))
+    }
+
+    // get every selected brush.
+    pub fn brush_list(&self) -> Vec<usize> {
+        self.brush.iter().copied().collect()
+    }
+
+    // get every selected entity.
+    pub fn entity_list(&self) -> Vec<usize> {
+        self.entity.iter().copied().collect()
+    }
+
+    // get every selected vertex of a brush.
+    pub fn vertex_list(&self, i: usize) -> Vec<usize> {
+        self.vertex
+            .range((i, 0)..(i + 1, 0))
+            .map(|(_, j)| *j)
+            .collect()
+    }
+
+    // get every selected edge of a brush.
+    pub fn edge_list(&self, i: usize) -> Vec<(usize, usize)> {
+        self.edge
+            .range((i, (0, 0))..(i + 1, (0, 0)))
+            .map(|(_, e)| *e)
+            .collect()
+    }
+
+    // get every selected face of a brush.
+    pub fn face_list(&self, i: usize) -> Vec<usize> {
+        self.face
+            .range((i, 0)..(i + 1, 0))
+            .map(|(_, j)| *j)
+            .collect()
+    }
+
+    // check if any vertex, edge or face of a brush is selected.
+    pub fn any_part(&self, i: usize) -> bool {
+        !self.vertex_list(i).is_empty()
+            || !self.edge_list(i).is_empty()
+            || !self.face_list(i).is_empty()
+    }
+
+    // deselect every vertex, edge and face of a brush. must be called whenever the vertex or face list of a brush is rebuilt.
+    pub fn remove_part(&mut self, i: usize) {
+        self.vertex.retain(|(b, _)| *b != i);
+        self.edge.retain(|(b, _)| *b != i);
+        self.face.retain(|(b, _)| *b != i);
+    }
+
+    // deselect every vertex, edge and face of every brush, keeping every brush selected.
+    pub fn remove_part_all(&mut self) {
+        self.vertex.clear();
+        self.edge.clear();
+        self.face.clear();
+    }
+
+    // deselect every brush, alongside every vertex, edge and face of it, keeping every entity selected.
+    pub fn remove_brush_all(&mut self) {
+        self.brush.clear();
+        self.remove_part_all();
+    }
+
+    // take note of a brush being removed from the world: it is deselected, and every brush after it moves down by one.
+    pub fn remove_brush(&mut self, i: usize) {
+        let shift = |b: usize| if b > i { b - 1 } else { b };
+
+        self.brush = self
+            .brush
+            .iter()
+            .filter(|b| **b != i)
+            .map(|b| shift(*b))
+            .collect();
+        self.vertex = self
+            .vertex
+            .iter()
+            .filter(|(b, _)| *b != i)
+            .map(|(b, j)| (shift(*b), *j))
+            .collect();
+        self.edge = self
+            .edge
+            .iter()
+            .filter(|(b, _)| *b != i)
+            .map(|(b, e)| (shift(*b), *e))
+            .collect();
+        self.face = self
+            .face
+            .iter()
+            .filter(|(b, _)| *b != i)
+            .map(|(b, j)| (shift(*b), *j))
+            .collect();
+    }
+
+    // take note of an entity being removed from the world: it is deselected, and every entity after it moves down by one.
+    pub fn remove_entity(&mut self, i: usize) {
+        self.entity = self
+            .entity
+            .iter()
+            .filter(|e| **e != i)
+            .map(|e| if *e > i { e - 1 } else { *e })
+            .collect();
+    }
+}
+
+//================================================================
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    // removing a brush must deselect it and every part of it, and move every brush after it down by one.
+    #[test]
+    fn remove_brush() {
+        let mut selection = Selection::default();
+
+        selection.add(SelectionItem::Brush(0));
+        selection.add(SelectionItem::Face(1, 2));
+        selection.add(SelectionItem::Vertex(2, 3));
+        selection.add(SelectionItem::Edge(3, (4, 1)));
+        selection.add(SelectionItem::Entity(2));
+
+        selection.remove_brush(1);
+
+        assert_eq!(selection.brush_list(), vec![0, 1, 2]);
+        assert!(selection.face_list(0).is_empty());
+        assert!(selection.face_list(1).is_empty());
+        assert!(selection.vertex(1, 3));
+        assert_eq!(selection.edge_list(2), vec![(1, 4)]);
+        assert_eq!(selection.entity_list(), vec![2]);
+    }
+
+    // removing an entity must deselect it, and move every entity after it down by one.
+    #[test]
+    fn remove_entity() {
+        let mut selection = Selection::default();
+
+        selection.add(SelectionItem::Entity(0));
+        selection.add(SelectionItem::Entity(2));
+        selection.add(SelectionItem::Entity(3));
+        selection.add(SelectionItem::Brush(2));
+
+        selection.remove_entity(2);
+
+        assert_eq!(selection.entity_list(), vec![0, 2]);
+        assert_eq!(selection.brush_list(), vec![2]);
+    }
+
+    // an edge is the same edge whichever way around its vertices are given.
+    #[test]
+    fn edge_order() {
+        let mut selection = Selection::default();
+
+        selection.add(SelectionItem::Edge(0, (5, 2)));
+
+        assert!(selection.edge(0, (2, 5)));
+        assert!(selection.has(SelectionItem::Edge(0, (5, 2))));
+
+        selection.toggle(SelectionItem::Edge(0, (2, 5)));
+
+        assert!(!selection.edge(0, (5, 2)));
+        assert!(selection.brush(0));
+    }
+
+    // selecting a part must select its brush, and deselecting the brush must deselect every part of it.
+    #[test]
+    fn part_brush() {
+        let mut selection = Selection::default();
+
+        selection.add(SelectionItem::Vertex(1, 0));
+        selection.add(SelectionItem::Face(1, 4));
+
+        assert!(selection.brush(1));
+        assert!(selection.any_part(1));
+
+        selection.remove(SelectionItem::Brush(1));
+
+        assert!(!selection.any_part(1));
+        assert!(selection.is_empty());
+    }
+
+    // replacing the brush list with a shorter one, such as after a merge, must not leave a brush selected past its end.
+    #[test]
+    fn set_brush_shrink() {
+        let mut world = World::default();
+
+        world.push_brush(Brush::default());
+        world.selection.add(SelectionItem::Brush(0));
+        world.selection.add(SelectionItem::Face(1, 2));
+        world.selection.add(SelectionItem::Entity(3));
+        world.set_brush(vec![(Brush::default(), true)]);
+
+        assert_eq!(world.selection.brush_list(), vec![0]);
+        assert!(!world.selection.any_part(1));
+        assert_eq!(world.selection.entity_list(), vec![3]);
+
+        world.set_brush(vec![(Brush::default(), false)]);
+
+        assert!(world.selection.brush_list().is_empty());
+    }
+
+    // inverting twice must give back the same selection.
+    #[test]
+    fn invert() {
+        let world = World::default();
+        let mut selection = Selection::default();
+
+        selection.add(SelectionItem::Brush(0));
+        selection.add(SelectionItem::Face(0, 1));
+        selection.invert(&world, SelectionKind::Face);
+
+        let face = world.brush[0].face.len();
+
+        assert_eq!(selection.face_list(0).len(), face - 1);
+        assert!(!selection.face(0, 1));
+
+        selection.invert(&world, SelectionKind::Face);
+
+        assert_eq!(selection.face_list(0), vec![1]);
+    }
+}
@@ -18,6 +18,7 @@
 use crate::editor::*;
 use crate::game::*;
 use crate::helper::*;
+use crate::map::*;
 use crate::window::*;
 
 //================================================================
@@ -29,7 +30,7 @@ use raylib::prelude::*;
 pub enum Status {
     Initial(InitialState, Asset, Window, Vec<Game>),
     Success(SuccessState, Asset, Window, Editor),
-    Failure(Window, String),
+    Failure(Failure, Asset, Window),
     Closure,
 }
 
@@ -37,14 +38,72 @@ impl Status {
     // get a new status instance.
     #[rustfmt::skip]
     pub fn new(handle: &mut RaylibHandle, thread: &RaylibThread) -> Self {
+        // offer to restore any autosave left behind by an editor that was not closed cleanly.
+        let recovery = Recovery::find();
+
         Self::Initial(
-            InitialState::default(),
+            if recovery.is_empty() { InitialState::default() } else { InitialState::Restore(recovery) },
             Asset::new(handle, thread),
             Window::new(handle, thread),
             Game::new_list(),
         )
     }
 
+    // get a new success status, or a failure status if the editor could not be made.
+    pub fn new_success(
+        handle: &mut RaylibHandle,
+        thread: &RaylibThread,
+        editor: Result<Editor, String>,
+        game: &Game,
+    ) -> Self {
+        match editor {
+            Ok(editor) => Self::Success(
+                SuccessState::Main,
+                Asset::new(handle, thread),
+                Window::new(handle, thread),
+                editor,
+            ),
+            Err(e) => Self::new_failure(handle, thread, e, Some(game.clone())),
+        }
+    }
+
+    // get a new failure status.
+    pub fn new_failure(
+        handle: &mut RaylibHandle,
+        thread: &RaylibThread,
+        text: String,
+        game: Option<Game>,
+    ) -> Self {
+        Self::Failure(
+            Failure::new(text, game),
+            Asset::new(handle, thread),
+            Window::new(handle, thread),
+        )
+    }
+
+    // carry the editor over from a success status into the failure status that followed it, so that the world is not lost.
+    pub fn carry(from: Status, into: Status) -> Status {
+        match (from, into) {
+            (Status::Success(_, _, _, mut editor), Status::Failure(mut failure, asset, window)) => {
+                // write a last snapshot, and keep it even if the crash-handler is left, until the editor is back up.
+                if let Err(e) = editor.recovery.write(
+                    &editor.game,
+                    &editor.world,
+                    &editor.path,
+                    editor.user.autosave_count,
+                ) {
+                    failure.info = Some(e);
+                }
+                editor.recovery.keep = true;
+
+                failure.game = Some(editor.game.clone());
+                failure.editor = Some(editor);
+                Status::Failure(failure, asset, window)
+            }
+            (_, into) => into,
+        }
+    }
+
     // create a RL context.
     pub fn window() -> (RaylibHandle, RaylibThread) {
         // create RL window, thread.
@@ -97,8 +156,10 @@ impl Status {
             let mut draw = handle.begin_drawing(thread);
             draw.clear_background(Color::WHITE);
 
-            // update editor.
-            editor.update(&mut draw, thread, asset);
+            // update editor. an error from Lua will go to the crash-handler.
+            if let Err(e) = editor.update(&mut draw, thread, asset) {
+                return Some(Status::new_failure(&mut draw, thread, e, None));
+            }
 
             // update window, change state if window has given back a new state.
             if let Some(status) = window.success(&mut draw, thread, status, asset, editor) {
@@ -114,10 +175,47 @@ impl Status {
     pub fn failure(
         handle: &mut RaylibHandle,
         thread: &RaylibThread,
+        failure: &mut Failure,
+        asset: &mut Asset,
         window: &mut Window,
-        text: &str,
     ) -> Option<Status> {
-        None
+        // begin drawing.
+        let mut draw = handle.begin_drawing(thread);
+        draw.clear_background(Color::WHITE);
+
+        // draw failure window.
+        window.failure(&mut draw, thread, failure, asset)
+    }
+}
+
+//================================================================
+
+// the crash-handler data: the error, alongside whatever could be kept from before it.
+pub struct Failure {
+    pub text: String,
+    pub info: Option<String>,
+    pub editor: Option<Editor>,
+    pub game: Option<Game>,
+}
+
+impl Failure {
+    pub const FILE_RECOVERY: &'static str = "recovery.mallet";
+
+    pub fn new(text: String, game: Option<Game>) -> Self {
+        Self {
+            text,
+            info: None,
+            editor: None,
+            game,
+        }
+    }
+
+    // split the error into the message and the Lua traceback, if there is one.
+    pub fn text_split(&self) -> (&str, Option<&str>) {
+        match self.text.find("stack traceback:") {
+            Some(i) => (self.text[..i].trim_end(), Some(&self.text[i..])),
+            None => (&self.text, None),
+        }
     }
 }
 
@@ -128,7 +226,11 @@ pub enum InitialState {
     #[default]
     Main,
     New,
-    //Load,
+    Load(Recent),
+    // a map whose game could not be found, waiting for the user to pick one.
+    Pick(String, Map),
+    // every autosave session left behind by an editor that was not closed cleanly.
+    Restore(Vec<RecoverySession>),
 }
 
 //================================================================
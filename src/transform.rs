@@ -0,0 +1,508 @@
+/*
+* BSD Zero Clause License
+*
+* Copyright (c) 2025 sockentrocken
+*
+* Permission to use, copy, modify, and/or distribute this software for any
+* purpose with or without fee is hereby granted.
+*
+* THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHOR DISCLAIMS ALL WARRANTIES WITH
+* REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF MERCHANTABILITY
+* AND FITNESS. IN NO EVENT SHALL THE AUTHOR BE LIABLE FOR ANY SPECIAL, DIRECT,
+* INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES WHATSOEVER RESULTING FROM
+* LOSS OF USE, DATA OR PROFITS, WHETHER IN AN ACTION OF CONTRACT, NEGLIGENCE OR
+* OTHER TORTIOUS ACTION, ARISING OUT OF OR IN CONNECTION WITH THE USE OR
+* PERFORMANCE OF THIS SOFTWARE.
+*/
+
+use crate::editor::*;
+use crate::geometry::*;
+
+//================================================================
+
+use raylib::prelude::*;
+
+//================================================================
+
+// a handle of the transform gizmo.
+#[derive(Copy, Clone, PartialEq)]
+pub enum TransformHandle {
+    // move or scale along an axis, or turn around it.
+    Axis(usize),
+    // move or scale along the plane across an axis.
+    Plane(usize),
+}
+
+// a drag of a handle, from the moment it was grabbed.
+struct TransformDrag {
+    handle: TransformHandle,
+    view: usize,
+    pivot: Vector3,
+    shape: f32,
+    plane: Plane,
+    start: Vector3,
+    world: World,
+    text: String,
+}
+
+// the state of the transform gizmo: a move, turn or scale gizmo drawn at the middle of the selection, based on the widget.
+#[derive(Default)]
+pub struct Transform {
+    drag: Option<TransformDrag>,
+}
+
+impl Transform {
+    const AXIS: [Vector3; 3] = [
+        Vector3::new(1.0, 0.0, 0.0),
+        Vector3::new(0.0, 1.0, 0.0),
+        Vector3::new(0.0, 0.0, 1.0),
+    ];
+    const COLOR: [Color; 3] = [Color::RED, Color::GREEN, Color::BLUE];
+    // size of the gizmo, as a fraction of the height of the view.
+    const SHAPE: f32 = 0.2;
+    // maximum distance from the ray to a handle, for it to be picked, as a fraction of the size of the gizmo.
+    const PICK_RANGE: f32 = 0.08;
+    // span of a plane handle, as a fraction of the size of the gizmo.
+    const PLANE_MIN: f32 = 0.25;
+    const PLANE_MAX: f32 = 0.5;
+
+    // check if a handle is being dragged.
+    pub fn active(&self) -> bool {
+        self.drag.is_some()
+    }
+
+    // get the middle of every selected brush and entity, if any.
+    pub fn pivot(world: &World) -> Option<Vector3> {
+        let mut min = Vector3::new(f32::MAX, f32::MAX, f32::MAX);
+        let mut max = Vector3::new(f32::MIN, f32::MIN, f32::MIN);
+        let mut any = false;
+
+        let point = world
+            .selection
+            .brush_list()
+            .into_iter()
+            .flat_map(|i| world.brush[i].vertex.iter().map(|v| v.point))
+            .chain(
+                world
+                    .selection
+                    .entity_list()
+                    .into_iter()
+                    .map(|i| world.entity[i].position),
+            );
+
+        for p in point {
+            min = Vector3::new(min.x.min(p.x), min.y.min(p.y), min.z.min(p.z));
+            max = Vector3::new(max.x.max(p.x), max.y.max(p.y), max.z.max(p.z));
+            any = true;
+        }
+
+        any.then(|| (min + max) * 0.5)
+    }
+
+    // get the size of the gizmo, so that it is the same size on screen in every view, however far the camera is.
+    fn shape(camera: &Camera3D, pivot: Vector3) -> f32 {
+        match camera.camera_type() {
+            CameraProjection::CAMERA_PERSPECTIVE => {
+                (camera.position - pivot).length()
+                    * (camera.fovy * 0.5 * DEG2RAD as f32).tan()
+                    * Self::SHAPE
+            }
+            CameraProjection::CAMERA_ORTHOGRAPHIC => camera.fovy * 0.5 * Self::SHAPE,
+        }
+    }
+
+    // get the point a ray hits a plane at, if any.
+    fn cast(ray: Ray, plane: &Plane) -> Option<Vector3> {
+        let den = plane.normal.dot(ray.direction);
+
+        if den.abs() <= f32::EPSILON {
+            return None;
+        }
+
+        let t = (plane.distance - plane.normal.dot(ray.position)) / den;
+
+        (t >= 0.0).then(|| ray.position + ray.direction * t)
+    }
+
+    // get the plane a handle is dragged along: the plane across the axis for a plane or turn handle, and the plane along the axis
+    // that faces the camera the most for an axis handle.
+    fn plane(
+        widget: &Widget,
+        handle: TransformHandle,
+        pivot: Vector3,
+        look: Vector3,
+    ) -> Option<Plane> {
+        let normal = match (widget, handle) {
+            (Widget::Rotation, TransformHandle::Axis(i)) | (_, TransformHandle::Plane(i)) => {
+                Self::AXIS[i]
+            }
+            (_, TransformHandle::Axis(i)) => {
+                let axis = Self::AXIS[i];
+                let normal = look - axis * look.dot(axis);
+
+                if normal.length() <= Plane::EPSILON {
+                    return None;
+                }
+
+                normal.normalized()
+            }
+        };
+
+        Some(Plane::new(normal, normal.dot(pivot)))
+    }
+
+    // get the direction the camera is looking at the pivot from.
+    fn look(camera: &Camera3D, pivot: Vector3) -> Vector3 {
+        match camera.camera_type() {
+            CameraProjection::CAMERA_PERSPECTIVE => (pivot - camera.position).normalized(),
+            CameraProjection::CAMERA_ORTHOGRAPHIC => (camera.target - camera.position).normalized(),
+        }
+    }
+
+    // get the handle under a ray, if any. the one closest along the ray wins.
+    pub fn pick(
+        widget: &Widget,
+        world: &World,
+        camera: &Camera3D,
+        ray: Ray,
+    ) -> Option<TransformHandle> {
+        if !matches!(widget, Widget::Position | Widget::Rotation | Widget::Scale) {
+            return None;
+        }
+
+        let pivot = Self::pivot(world)?;
+        let shape = Self::shape(camera, pivot);
+        let mut hit: Option<(TransformHandle, f32)> = None;
+
+        let mut push = |handle: TransformHandle, distance: f32| {
+            if hit.is_none_or(|(_, d)| distance < d) {
+                hit = Some((handle, distance));
+            }
+        };
+
+        for (i, axis) in Self::AXIS.iter().enumerate() {
+            if matches!(widget, Widget::Rotation) {
+                // a turn handle is a ring around the axis.
+                let plane = Plane::new(*axis, axis.dot(pivot));
+
+                if let Some(point) = Self::cast(ray, &plane) {
+                    if ((point - pivot).length() - shape).abs() <= shape * Self::PICK_RANGE {
+                        push(TransformHandle::Axis(i), (point - ray.position).length());
+                    }
+                }
+
+                continue;
+            }
+
+            // an axis handle is a line from the pivot, along the axis.
+            let u = *axis * shape;
+            let w = pivot - ray.position;
+            let ud = u.dot(ray.direction);
+            let dd = ray.direction.dot(ray.direction);
+            let den = u.dot(u) * dd - ud * ud;
+
+            if den > f32::EPSILON {
+                let s = ((ud * ray.direction.dot(w) - dd * u.dot(w)) / den).clamp(0.0, 1.0);
+                let point = pivot + u * s;
+                let t = ((point - ray.position).dot(ray.direction) / dd).max(0.0);
+
+                if (point - (ray.position + ray.direction * t)).length() <= shape * Self::PICK_RANGE
+                {
+                    push(TransformHandle::Axis(i), t);
+                }
+            }
+
+            // a plane handle is a square across the axis, between the other two.
+            let plane = Plane::new(*axis, axis.dot(pivot));
+
+            if let Some(point) = Self::cast(ray, &plane) {
+                let a = (point - pivot).dot(Self::AXIS[(i + 1) % 3]) / shape;
+                let b = (point - pivot).dot(Self::AXIS[(i + 2) % 3]) / shape;
+                let span = Self::PLANE_MIN..=Self::PLANE_MAX;
+
+                if span.contains(&a) && span.contains(&b) {
+                    push(TransformHandle::Plane(i), (point - ray.position).length());
+                }
+            }
+        }
+
+        hit.map(|(handle, _)| handle)
+    }
+
+    // grab the handle under a ray, if any. returns true if a handle was grabbed.
+    pub fn begin(
+        &mut self,
+        widget: &Widget,
+        world: &World,
+        view: usize,
+        camera: &Camera3D,
+        ray: Ray,
+    ) -> bool {
+        let (Some(handle), Some(pivot)) =
+            (Self::pick(widget, world, camera, ray), Self::pivot(world))
+        else {
+            return false;
+        };
+
+        let Some(plane) = Self::plane(widget, handle, pivot, Self::look(camera, pivot)) else {
+            return false;
+        };
+
+        let Some(start) = Self::cast(ray, &plane) else {
+            return false;
+        };
+
+        self.drag = Some(TransformDrag {
+            handle,
+            view,
+            pivot,
+            shape: Self::shape(camera, pivot),
+            plane,
+            start,
+            world: world.clone(),
+            text: String::default(),
+        });
+
+        true
+    }
+
+    // let go of the handle. gives back the world as it was when the handle was grabbed, if one was.
+    pub fn end(&mut self) -> Option<World> {
+        self.drag.take().map(|drag| drag.world)
+    }
+
+    // get the view the handle was grabbed in, if any.
+    pub fn view(&self) -> Option<usize> {
+        self.drag.as_ref().map(|drag| drag.view)
+    }
+
+    // drag the grabbed handle to a ray. the selection is transformed from where it was when the handle was grabbed, so that it does not drift.
+    pub fn update(
+        &mut self,
+        widget: &Widget,
+        world: &mut World,
+        user: &User,
+        asset: &Asset,
+        ray: Ray,
+    ) {
+        let Some(drag) = &mut self.drag else {
+            return;
+        };
+
+        let Some(point) = Self::cast(ray, &drag.plane) else {
+            return;
+        };
+
+        let pivot = drag.pivot;
+        let delta = point - drag.start;
+
+        // the move, turn and scale to apply, from the pivot.
+        let mut value = Vector3::zero();
+        let mut angle = Vector3::zero();
+        let mut scale = Vector3::one();
+
+        match (widget, drag.handle) {
+            (Widget::Position, handle) => {
+                let mask = match handle {
+                    TransformHandle::Axis(i) => Self::AXIS[i],
+                    TransformHandle::Plane(i) => Vector3::one() - Self::AXIS[i],
+                };
+
+                value = user.snap_point(delta * mask);
+                drag.text = format!("{:.2}, {:.2}, {:.2}", value.x, value.y, value.z);
+            }
+            (Widget::Rotation, TransformHandle::Axis(i)) => {
+                let a = drag.start - pivot;
+                let b = point - pivot;
+                let turn = a.cross(b).dot(Self::AXIS[i]).atan2(a.dot(b)) * RAD2DEG as f32;
+                let turn = user.snap_angle(turn);
+
+                angle = Self::AXIS[i] * turn;
+                drag.text = format!("{turn:.1}°");
+            }
+            (Widget::Scale, handle) => {
+                let (length, mask) = match handle {
+                    TransformHandle::Axis(i) => (delta.dot(Self::AXIS[i]), Self::AXIS[i]),
+                    TransformHandle::Plane(i) => {
+                        let mask = Vector3::one() - Self::AXIS[i];
+                        (delta.dot(mask) / 2.0_f32.sqrt(), mask)
+                    }
+                };
+
+                let factor = user.snap_scale(1.0 + length / drag.shape);
+
+                scale = Vector3::one() + mask * (factor - 1.0);
+                drag.text = format!("x{factor:.3}");
+            }
+            _ => return,
+        }
+
+        let matrix = Matrix::rotate(angle.normalized(), angle.length() * DEG2RAD as f32);
+        let call = |p: Vector3| {
+            let p = (p - pivot) * scale;
+            let p = if angle.length() > 0.0 {
+                p.transform_with(matrix)
+            } else {
+                p
+            };
+
+            pivot + p + value
+        };
+
+        for (i, (brush, before)) in world.brush.iter_mut().zip(&drag.world.brush).enumerate() {
+            if !world.selection.brush(i) {
+                continue;
+            }
+
+            for (vertex, other) in brush.vertex.iter_mut().zip(&before.vertex) {
+                vertex.point = call(other.point);
+            }
+
+            brush.set_plane();
+
+            if user.texture_lock {
+                brush.lock(before, asset);
+            }
+        }
+
+        for (i, (entity, before)) in world.entity.iter_mut().zip(&drag.world.entity).enumerate() {
+            if !world.selection.entity(i) {
+                continue;
+            }
+
+            entity.position = call(before.position);
+            entity.rotation = before.rotation + angle;
+            entity.scale = before.scale * scale;
+        }
+    }
+
+    // draw the gizmo at the middle of the selection. the handle under the ray, or the one being dragged, is drawn in yellow.
+    pub fn draw(
+        &self,
+        widget: &Widget,
+        world: &World,
+        draw: &mut RaylibMode3D<RaylibTextureMode<RaylibDrawHandle>>,
+        camera: &Camera3D,
+        ray: Option<Ray>,
+    ) {
+        if !matches!(widget, Widget::Position | Widget::Rotation | Widget::Scale) {
+            return;
+        }
+
+        let Some(pivot) = Self::pivot(world) else {
+            return;
+        };
+
+        let shape = Self::shape(camera, pivot);
+        let hover = match &self.drag {
+            Some(drag) => Some(drag.handle),
+            None => ray.and_then(|ray| Self::pick(widget, world, camera, ray)),
+        };
+
+        let color = |handle: TransformHandle, i: usize| {
+            if hover == Some(handle) {
+                Color::YELLOW
+            } else {
+                Self::COLOR[i]
+            }
+        };
+
+        // draw the gizmo over everything else.
+        unsafe {
+            ffi::rlDrawRenderBatchActive();
+            ffi::rlDisableDepthTest();
+        }
+
+        for (i, axis) in Self::AXIS.iter().enumerate() {
+            if matches!(widget, Widget::Rotation) {
+                let u = Self::AXIS[(i + 1) % 3] * shape;
+                let v = Self::AXIS[(i + 2) % 3] * shape;
+                let count = 48;
+
+                for j in 0..count {
+                    let a = j as f32 / count as f32 * std::f32::consts::TAU;
+                    let b = (j + 1) as f32 / count as f32 * std::f32::consts::TAU;
+
+                    draw.draw_line_3D(
+                        pivot + u * a.cos() + v * a.sin(),
+                        pivot + u * b.cos() + v * b.sin(),
+                        color(TransformHandle::Axis(i), i),
+                    );
+                }
+
+                continue;
+            }
+
+            let tip = pivot + *axis * shape;
+            let axis_color = color(TransformHandle::Axis(i), i);
+
+            draw.draw_cylinder_ex(pivot, tip, shape * 0.01, shape * 0.01, 4, axis_color);
+
+            if matches!(widget, Widget::Scale) {
+                draw.draw_cube(tip, shape * 0.1, shape * 0.1, shape * 0.1, axis_color);
+            } else {
+                draw.draw_cylinder_ex(
+                    tip,
+                    tip + *axis * shape * 0.2,
+                    shape * 0.06,
+                    0.0,
+                    8,
+                    axis_color,
+                );
+            }
+
+            // the plane handle, drawn from both sides.
+            let u = Self::AXIS[(i + 1) % 3] * shape;
+            let v = Self::AXIS[(i + 2) % 3] * shape;
+            let plane_color = color(TransformHandle::Plane(i), i).alpha(0.5);
+            let point = [
+                pivot + u * Self::PLANE_MIN + v * Self::PLANE_MIN,
+                pivot + u * Self::PLANE_MAX + v * Self::PLANE_MIN,
+                pivot + u * Self::PLANE_MAX + v * Self::PLANE_MAX,
+                pivot + u * Self::PLANE_MIN + v * Self::PLANE_MAX,
+            ];
+
+            draw.draw_triangle3D(point[0], point[1], point[2], plane_color);
+            draw.draw_triangle3D(point[0], point[2], point[3], plane_color);
+            draw.draw_triangle3D(point[2], point[1], point[0], plane_color);
+            draw.draw_triangle3D(point[3], point[2], point[0], plane_color);
+        }
+
+        unsafe {
+            ffi::rlDrawRenderBatchActive();
+            ffi::rlEnableDepthTest();
+        }
+    }
+
+    // draw the change made by the drag so far, next to the pivot.
+    pub fn draw_text(
+        &self,
+        draw: &mut RaylibMode2D<RaylibTextureMode<RaylibDrawHandle>>,
+        asset: &Asset,
+        camera: &Camera3D,
+        port: Vector2,
+    ) {
+        let Some(drag) = &self.drag else {
+            return;
+        };
+
+        let point = draw.get_world_to_screen_ex(drag.pivot, camera, port.x as i32, port.y as i32);
+        let font = asset.inner.font.measure_text(&drag.text, 24.0, 1.0);
+        let point = point + Vector2::new(16.0, -font.y - 16.0);
+
+        draw.draw_rectangle_rounded(
+            Rectangle::new(point.x, point.y, font.x + 8.0, font.y),
+            0.25,
+            4,
+            Color::new(0, 0, 0, 255),
+        );
+        draw.draw_text_ex(
+            &asset.inner.font,
+            &drag.text,
+            point + Vector2::new(4.0, 0.0),
+            24.0,
+            1.0,
+            Color::WHITE,
+        );
+    }
+}
@@ -15,9 +15,13 @@
 * PERFORMANCE OF THIS SOFTWARE.
 */
 
+use crate::check::*;
 use crate::editor::*;
 use crate::game::*;
-use crate::helper::screen_shape;
+use crate::helper::{error, screen_shape};
+use crate::map::*;
+use crate::primitive::*;
+use crate::selection::*;
 use crate::status::*;
 
 //================================================================
@@ -96,6 +100,7 @@ impl Window {
 
     pub const TOOL_SHAPE: f32 = 56.0;
     pub const EDIT_SHAPE: f32 = 400.0;
+    const PREVIEW_SHAPE: f32 = 128.0;
 
     //================================================================
 
@@ -145,7 +150,9 @@ impl Window {
                 if self.button(draw, asset, "New Map").0.click {
                     *status = InitialState::New;
                 }
-                self.button(draw, asset, "Load Map");
+                if self.button(draw, asset, "Load Map").0.click {
+                    *status = InitialState::Load(Recent::new());
+                }
                 if self.button(draw, asset, "Exit Mallet").0.click {
                     return Some(Status::Closure);
                 }
@@ -167,12 +174,8 @@ impl Window {
 
                 for g in game {
                     if self.button(draw, asset, &g.info.name).0.click {
-                        return Some(Status::Success(
-                            SuccessState::Main,
-                            Asset::new(draw, thread),
-                            Window::new(draw, thread),
-                            Editor::new(draw, thread, g.clone()),
-                        ));
+                        let editor = Editor::new(draw, thread, g.clone());
+                        return Some(Status::new_success(draw, thread, editor, g));
                     }
                 }
 
@@ -182,6 +185,239 @@ impl Window {
                     *status = InitialState::Main;
                 }
             }
+            InitialState::Load(recent) => {
+                let card_shape = Rectangle::new(0.0, 0.0, draw_shape.x, 48.0);
+
+                self.card_sharp(draw, card_shape, Window::COLOR_PRIMARY_MAIN, true);
+
+                self.font(
+                    draw,
+                    asset,
+                    "Map Selection",
+                    Vector2::new(16.0, 12.0),
+                    Window::COLOR_TEXT_WHITE,
+                );
+
+                self.point(Vector2::new(20.0, 72.0));
+
+                let mut pick: Option<String> = None;
+
+                for path in &recent.path {
+                    if !std::path::Path::new(path).is_file() {
+                        continue;
+                    }
+
+                    if self
+                        .button_shape(
+                            draw,
+                            asset,
+                            path,
+                            Some(Vector2::new(draw_shape.x - 40.0, 32.0)),
+                            None,
+                            true,
+                        )
+                        .0
+                        .click
+                    {
+                        pick = Some(path.clone());
+                    }
+                }
+
+                self.point(Vector2::new(20.0, draw_shape.y - 96.0));
+
+                if self.button(draw, asset, "Browse").0.click {
+                    pick = Map::pick_load();
+                }
+
+                if self.button(draw, asset, "Back").0.click {
+                    *status = InitialState::Main;
+                }
+
+                if let Some(path) = pick {
+                    match Map::new_from_file(&path) {
+                        Ok(map) => {
+                            // find the game the map was made for, otherwise, let the user pick one.
+                            if let Some(g) = game.iter().find(|g| g.path == map.game) {
+                                let editor =
+                                    Editor::new_from_map(draw, thread, g.clone(), map, path);
+                                return Some(Status::new_success(draw, thread, editor, g));
+                            } else {
+                                *status = InitialState::Pick(path, map);
+                            }
+                        }
+                        Err(e) => error(&e),
+                    }
+                }
+            }
+            InitialState::Pick(path, map) => {
+                let card_shape = Rectangle::new(0.0, 0.0, draw_shape.x, 48.0);
+
+                self.card_sharp(draw, card_shape, Window::COLOR_PRIMARY_MAIN, true);
+
+                self.font(
+                    draw,
+                    asset,
+                    "Game Selection",
+                    Vector2::new(16.0, 12.0),
+                    Window::COLOR_TEXT_WHITE,
+                );
+
+                self.point(Vector2::new(20.0, 72.0));
+
+                self.text(
+                    draw,
+                    asset,
+                    &format!(
+                        "Could not find the game at \"{}\" for map \"{path}\".",
+                        map.game
+                    ),
+                    Window::COLOR_TEXT_BLACK,
+                );
+                self.text(
+                    draw,
+                    asset,
+                    "Pick a game to load the map with:",
+                    Window::COLOR_TEXT_BLACK,
+                );
+
+                let mut pick: Option<&Game> = None;
+
+                for g in game {
+                    if self.button(draw, asset, &g.info.name).0.click {
+                        pick = Some(g);
+                    }
+                }
+
+                self.point(Vector2::new(20.0, draw_shape.y - 56.0));
+
+                if self.button(draw, asset, "Back").0.click {
+                    *status = InitialState::Load(Recent::new());
+                }
+
+                if let Some(g) = pick {
+                    if let InitialState::Pick(path, map) = std::mem::take(status) {
+                        let editor = Editor::new_from_map(draw, thread, g.clone(), map, path);
+                        return Some(Status::new_success(draw, thread, editor, g));
+                    }
+                }
+            }
+            InitialState::Restore(recovery) => {
+                let card_shape = Rectangle::new(0.0, 0.0, draw_shape.x, 48.0);
+
+                self.card_sharp(draw, card_shape, Window::COLOR_PRIMARY_MAIN, true);
+
+                self.font(
+                    draw,
+                    asset,
+                    "Recovery",
+                    Vector2::new(16.0, 12.0),
+                    Window::COLOR_TEXT_WHITE,
+                );
+
+                self.point(Vector2::new(20.0, 72.0));
+
+                self.text(
+                    draw,
+                    asset,
+                    "Mallet was not closed cleanly last time.",
+                    Window::COLOR_TEXT_BLACK,
+                );
+                self.text(
+                    draw,
+                    asset,
+                    "Pick an autosave to restore:",
+                    Window::COLOR_TEXT_BLACK,
+                );
+
+                let mut pick: Option<usize> = None;
+
+                for (i, session) in recovery.iter().enumerate() {
+                    let time = session
+                        .time()
+                        .elapsed()
+                        .map(|time| time.as_secs() / 60)
+                        .unwrap_or_default();
+                    let name = session.path.as_deref().unwrap_or("Untitled");
+
+                    if self
+                        .button_shape(
+                            draw,
+                            asset,
+                            &format!("{name} ({time} minute(s) ago)"),
+                            Some(Vector2::new(draw_shape.x - 40.0, 32.0)),
+                            None,
+                            true,
+                        )
+                        .0
+                        .click
+                    {
+                        pick = Some(i);
+                    }
+                }
+
+                self.point(Vector2::new(20.0, draw_shape.y - 96.0));
+
+                let mut back = false;
+
+                // leave every session on disk, to be offered again on the next start-up.
+                if self.button(draw, asset, "Later").0.click {
+                    back = true;
+                }
+
+                if self.button(draw, asset, "Discard").0.click {
+                    for session in recovery.iter() {
+                        session.remove();
+                    }
+
+                    back = true;
+                }
+
+                if let Some(i) = pick {
+                    let session = &recovery[i];
+
+                    match session.map() {
+                        Ok(map) => {
+                            if let Some(g) = game.iter().find(|g| g.path == map.game) {
+                                let editor = Editor::new_from_recovery(
+                                    draw,
+                                    thread,
+                                    g.clone(),
+                                    map,
+                                    session.path.clone(),
+                                );
+
+                                // write the restored world to the new session, before removing the old one.
+                                if let Ok(mut editor) = editor {
+                                    if let Err(e) = editor.recovery.write(
+                                        &editor.game,
+                                        &editor.world,
+                                        &editor.path,
+                                        editor.user.autosave_count,
+                                    ) {
+                                        error(&e);
+                                    } else {
+                                        session.remove();
+                                    }
+
+                                    return Some(Status::new_success(draw, thread, Ok(editor), g));
+                                }
+
+                                return Some(Status::new_success(draw, thread, editor, g));
+                            } else {
+                                error(&format!(
+                                    "Could not find the game at \"{}\" for the autosave.",
+                                    map.game
+                                ));
+                            }
+                        }
+                        Err(e) => error(&e),
+                    }
+                }
+
+                if back {
+                    *status = InitialState::Main;
+                }
+            }
         }
 
         if draw.window_should_close() {
@@ -234,7 +470,9 @@ impl Window {
                     self.draw_entity(draw, asset, editor);
                 }
 
-                self.draw_widget(draw, asset, thread, editor);
+                if let Some(status) = self.draw_widget(draw, asset, thread, editor) {
+                    return Some(status);
+                }
 
                 if self.widget(
                     draw,
@@ -272,6 +510,123 @@ impl Window {
         }
     }
 
+    // draw the failure window (crash-handler).
+    pub fn failure(
+        &mut self,
+        draw: &mut RaylibDrawHandle,
+        thread: &RaylibThread,
+        failure: &mut Failure,
+        asset: &Asset,
+    ) -> Option<Status> {
+        if draw.window_should_close() {
+            return Some(Status::Closure);
+        }
+
+        self.begin();
+
+        let draw_shape = screen_shape(draw);
+        let card_shape = Rectangle::new(0.0, 0.0, draw_shape.x, 48.0);
+
+        self.card_sharp(draw, card_shape, Window::COLOR_PRIMARY_MAIN, true);
+
+        self.font(
+            draw,
+            asset,
+            "Fatal Error",
+            Vector2::new(16.0, 12.0),
+            Window::COLOR_TEXT_WHITE,
+        );
+
+        self.point(Vector2::new(20.0, 72.0));
+
+        let (text, trace) = failure.text_split();
+
+        for line in text.lines() {
+            self.text(draw, asset, line, Window::COLOR_TEXT_BLACK);
+        }
+
+        if let Some(trace) = trace {
+            let trace = trace.to_string();
+
+            self.scroll(
+                asset,
+                draw,
+                "##Traceback",
+                Rectangle::new(
+                    self.point.x,
+                    self.point.y,
+                    draw_shape.x - 40.0,
+                    draw_shape.y - self.point.y - 160.0,
+                ),
+                |window, draw, _| {
+                    for line in trace.lines() {
+                        window.text(draw, asset, line.trim_end(), Window::COLOR_TEXT_BLACK);
+                    }
+                },
+            );
+        }
+
+        self.point(Vector2::new(20.0, draw_shape.y - 144.0));
+
+        if let Some(info) = &failure.info {
+            self.text(draw, asset, info, Window::COLOR_TEXT_BLACK);
+        }
+
+        self.point(Vector2::new(20.0, draw_shape.y - 96.0));
+
+        if failure.game.is_some() && self.button(draw, asset, "Reload Scripts").0.click {
+            // reload the editor we already have, or make a new one if the error happened while making it.
+            let result = match &mut failure.editor {
+                Some(editor) => editor.reload(draw, thread),
+                None => Editor::new(draw, thread, failure.game.clone().unwrap()).map(|editor| {
+                    failure.editor = Some(editor);
+                }),
+            };
+
+            match result {
+                Ok(_) => {
+                    if let Some(mut editor) = failure.editor.take() {
+                        editor.recovery.keep = false;
+
+                        return Some(Status::Success(
+                            SuccessState::Main,
+                            Asset::new(draw, thread),
+                            Window::new(draw, thread),
+                            editor,
+                        ));
+                    }
+                }
+                Err(e) => {
+                    failure.text = e;
+                    failure.info = None;
+                }
+            }
+        }
+
+        if let Some(editor) = &failure.editor {
+            if self.button(draw, asset, "Save Recovery").0.click {
+                let result =
+                    Map::new(&editor.game, &editor.world).save_file(Failure::FILE_RECOVERY);
+
+                failure.info = Some(match result {
+                    Ok(_) => format!("Saved the map to \"{}\".", Failure::FILE_RECOVERY),
+                    Err(e) => e,
+                });
+            }
+        }
+
+        if self.button(draw, asset, "Return").0.click {
+            return Some(Status::Initial(
+                InitialState::default(),
+                Asset::new(draw, thread),
+                Window::new(draw, thread),
+                Game::new_list(),
+            ));
+        }
+
+        None
+    }
+
     //================================================================
 
     // reset the state of the window before drawing.
@@ -325,18 +680,125 @@ impl Window {
         if self.widget(draw, asset, Vector2::new(point + (shift * x), 12.0), "Scale",    &editor.asset.inner.scale,    &editor.user.scale,    !matches!(editor.widget, Widget::Scale))    { editor.widget = Widget::Scale;    }; x += 1.0;
         if self.widget(draw, asset, Vector2::new(point + (shift * x), 12.0), "Vertex",   &editor.asset.inner.vertex,   &editor.user.vertex,   !matches!(editor.widget, Widget::Vertex))   { editor.widget = Widget::Vertex;   }; x += 1.0;
         if self.widget(draw, asset, Vector2::new(point + (shift * x), 12.0), "Edge",     &editor.asset.inner.edge,     &editor.user.edge,     !matches!(editor.widget, Widget::Edge))     { editor.widget = Widget::Edge;     }; x += 1.0;
-        if self.widget(draw, asset, Vector2::new(point + (shift * x), 12.0), "Face",     &editor.asset.inner.face,     &editor.user.face,     !matches!(editor.widget, Widget::Face))     { editor.widget = Widget::Face;     };
+        if self.widget(draw, asset, Vector2::new(point + (shift * x), 12.0), "Face",     &editor.asset.inner.face,     &editor.user.face,     !matches!(editor.widget, Widget::Face))     { editor.widget = Widget::Face;     }; x += 1.0;
+        if self.widget(draw, asset, Vector2::new(point + (shift * x), 12.0), "Clip",     &editor.asset.inner.clip,     &editor.user.clip,     !matches!(editor.widget, Widget::Clip))     { editor.widget = Widget::Clip;     };
 
         let mut x = 0.0;
         let point = screen_shape.x - 220.0;
         let shift = 44.0;
 
         if self.widget(draw, asset, Vector2::new(point + (shift * x), 12.0), "User",   &editor.asset.inner.user,   &editor.user.user, true)   { println!("1"); }; x += 1.0;
-        if self.widget(draw, asset, Vector2::new(point + (shift * x), 12.0), "Reload", &editor.asset.inner.reload, &editor.user.reload, true) { editor.reload(draw, thread); }; x += 1.0;
-        if self.widget(draw, asset, Vector2::new(point + (shift * x), 12.0), "Import", &editor.asset.inner.import, &editor.user.import, true) { println!("3"); }; x += 1.0;
-        if self.widget(draw, asset, Vector2::new(point + (shift * x), 12.0), "Export", &editor.asset.inner.export, &editor.user.export, true) { println!("4"); }; x += 1.0;
+        if self.widget(draw, asset, Vector2::new(point + (shift * x), 12.0), "Reload", &editor.asset.inner.reload, &editor.user.reload, true) { if let Err(e) = editor.reload(draw, thread) { return Some(Status::new_failure(draw, thread, e, None)); } }; x += 1.0;
+        if self.widget(draw, asset, Vector2::new(point + (shift * x), 12.0), "Import", &editor.asset.inner.import, &editor.user.import, true) { editor.import(); }; x += 1.0;
+        if self.widget(draw, asset, Vector2::new(point + (shift * x), 12.0), "Export", &editor.asset.inner.export, &editor.user.export, true) { editor.export(); }; x += 1.0;
         if self.widget(draw, asset, Vector2::new(point + (shift * x), 12.0), "Exit",   &editor.asset.inner.exit,   &editor.user.exit, true)   { };
 
+        if editor.user.save.get_press(draw) {
+            editor.save();
+        }
+
+        if editor.user.undo.get_press(draw) {
+            editor.history.undo(&mut editor.world);
+        }
+
+        if editor.user.redo.get_press(draw) {
+            editor.history.redo(&mut editor.world);
+        }
+
+        if editor.user.carve.get_press(draw) {
+            editor.carve();
+        }
+
+        if editor.user.hollow.get_press(draw) {
+            editor.hollow();
+        }
+
+        if editor.user.merge.get_press(draw) {
+            editor.merge();
+        }
+
+        if editor.user.extrude.get_press(draw) {
+            editor.extrude();
+        }
+
+        if editor.user.grow.get_press(draw) {
+            editor.grow();
+        }
+
+        if editor.user.inset.get_press(draw) {
+            editor.inset();
+        }
+
+        if editor.user.lock.get_press(draw) {
+            editor.user.texture_lock = !editor.user.texture_lock;
+        }
+
+        if editor.user.grid_up.get_press(draw) {
+            editor.user.set_grid(true);
+        }
+
+        if editor.user.grid_down.get_press(draw) {
+            editor.user.set_grid(false);
+        }
+
+        if editor.user.snap.get_press(draw) {
+            editor.user.snap_position = !editor.user.snap_position;
+        }
+
+        if editor.user.snap_angle.get_press(draw) {
+            editor.user.snap_rotation = !editor.user.snap_rotation;
+        }
+
+        if editor.user.snap_step.get_press(draw) {
+            editor.user.snap_scale = !editor.user.snap_scale;
+        }
+
+        // the selection commands work on every brush and entity with a transform widget, or on every part of every
+        // selected brush otherwise.
+        if editor.user.select_all.get_press(draw) {
+            editor.world.select_all(SelectionKind::new(&editor.widget));
+        }
+
+        if editor.user.select_none.get_press(draw) {
+            editor.world.select_none();
+        }
+
+        if editor.user.select_invert.get_press(draw) {
+            editor.world.select_invert(SelectionKind::new(&editor.widget));
+        }
+
+        {
+            // the grid and snap tool-tips sit above every other tool-tip.
+            let point = Vector2::new(20.0, screen_shape.y - if matches!(editor.widget, Widget::Clip) { 240.0 } else { 204.0 });
+            let state = |value: bool| if value { "On" } else { "Off" };
+
+            self.tool_tip(draw, asset, point, &format!("Grid: {}", editor.user.grid), Some(&editor.user.grid_up));
+            self.tool_tip(draw, asset, point + Vector2::new(0.0, 36.0), &format!("Snap: {}", state(editor.user.snap_position)), Some(&editor.user.snap));
+            self.tool_tip(draw, asset, point + Vector2::new(0.0, 72.0), &format!("Angle Snap: {}", state(editor.user.snap_rotation)), Some(&editor.user.snap_angle));
+            self.tool_tip(draw, asset, point + Vector2::new(0.0, 108.0), &format!("Scale Snap: {}", state(editor.user.snap_scale)), Some(&editor.user.snap_step));
+        }
+
+        if !matches!(editor.widget, Widget::Clip) {
+            let point = Vector2::new(20.0, screen_shape.y - 60.0);
+
+            self.tool_tip(draw, asset, point, &format!("Texture Lock: {}", if editor.user.texture_lock { "On" } else { "Off" }), Some(&editor.user.lock));
+        }
+
+        if matches!(editor.widget, Widget::Clip) {
+            let point = Vector2::new(20.0, screen_shape.y - 96.0);
+
+            self.tool_tip(draw, asset, point, "Apply Clip", Some(&editor.user.clip_apply));
+            self.tool_tip(draw, asset, point + Vector2::new(0.0, 36.0), &format!("Keep: {}", editor.clip.side.name()), Some(&editor.user.clip_side));
+
+            if editor.user.clip_apply.get_press(draw) {
+                editor.clip();
+            }
+
+            if editor.user.clip_side.get_press(draw) {
+                editor.clip.side = editor.clip.side.next();
+            }
+        }
+
         None
     }
 
@@ -348,66 +810,142 @@ impl Window {
 
         self.point(Vector2::new(point, 120.0));
 
-        for entity in &mut editor.world.entity {
-            if entity.focus {
-                self.scroll(asset, draw, "##Entity Data",  Rectangle::new(self.point.x, self.point.y, Self::EDIT_SHAPE - 24.0, (draw_shape.y * 0.5) - self.point.y - 32.0), |window, draw, scroll| {
-                    window.text(draw, asset, &entity.meta.info, Self::COLOR_TEXT_WHITE);
-
-                    window.drop(&editor.asset, draw, "Position", |window, draw| {
-                        window.record_number(draw, asset, "X", &mut entity.position.x);
-                        window.record_number(draw, asset, "Y", &mut entity.position.y);
-                        window.record_number(draw, asset, "Z", &mut entity.position.z);
-                    });
-                    window.drop(&editor.asset, draw, "Rotation", |window, draw| {
-                        window.record_number(draw, asset, "X", &mut entity.rotation.x);
-                        window.record_number(draw, asset, "Y", &mut entity.rotation.y);
-                        window.record_number(draw, asset, "Z", &mut entity.rotation.z);
-                    });
-                    window.drop(&editor.asset, draw, "Scale", |window, draw| {
-                        window.record_number(draw, asset, "X", &mut entity.scale.x);
-                        window.record_number(draw, asset, "Y", &mut entity.scale.y);
-                        window.record_number(draw, asset, "Z", &mut entity.scale.z);
-                    });
-
-                    for (_, v) in &mut entity.meta.data {
-                        match &mut v.kind {
-                            serde_json::Value::Bool(ref mut value) => {
-                                window.toggle(draw, asset, &v.info, value);
-                            },
-                            serde_json::Value::Number(ref mut value) => {
-                                let mut cast = value.as_f64().unwrap() as f32;
-
-                                window.record_number(draw, asset, &v.info, &mut cast);
+        // an edit of an entity ends once the value being dragged or clicked is let go of.
+        if editor.user.interact.get_release(draw) && matches!(editor.history.group(), Some(HistoryGroup::Entity(_))) {
+            editor.history.end_group();
+        }
+
+        if let Some(i) = editor.world.selection.entity_list().first().copied() {
+            let entity = &mut editor.world.entity[i];
+            let before = entity.clone();
+
+            self.scroll(asset, draw, "##Entity Data",  Rectangle::new(self.point.x, self.point.y, Self::EDIT_SHAPE - 24.0, (draw_shape.y * 0.5) - self.point.y - 32.0), |window, draw, _| {
+                window.text(draw, asset, &entity.meta.info, Self::COLOR_TEXT_WHITE);
+
+                window.drop(&editor.asset, draw, "Position", |window, draw| {
+                    window.record_number(draw, asset, "X", &mut entity.position.x);
+                    window.record_number(draw, asset, "Y", &mut entity.position.y);
+                    window.record_number(draw, asset, "Z", &mut entity.position.z);
+                });
+                window.drop(&editor.asset, draw, "Rotation", |window, draw| {
+                    window.record_number(draw, asset, "X", &mut entity.rotation.x);
+                    window.record_number(draw, asset, "Y", &mut entity.rotation.y);
+                    window.record_number(draw, asset, "Z", &mut entity.rotation.z);
+                });
+                window.drop(&editor.asset, draw, "Scale", |window, draw| {
+                    window.record_number(draw, asset, "X", &mut entity.scale.x);
+                    window.record_number(draw, asset, "Y", &mut entity.scale.y);
+                    window.record_number(draw, asset, "Z", &mut entity.scale.z);
+                });
 
+                for v in entity.meta.data.values_mut() {
+                    match &mut v.kind {
+                        serde_json::Value::Bool(ref mut value) => {
+                            window.toggle(draw, asset, &v.info, value);
+                        },
+                        serde_json::Value::Number(ref mut value) => {
+                            let mut cast = value.as_f64().unwrap() as f32;
+                            let copy = cast;
+
+                            window.record_number(draw, asset, &v.info, &mut cast);
+
+                            // only write back on change, as the cast to f32 would otherwise change the value every frame.
+                            if cast != copy {
                                 *value = Number::from_f64(cast as f64).unwrap();
-                            },
-                            serde_json::Value::String(ref mut value) => {
-                                window.record(draw, asset, &v.info, value);
-                            },
-                            _ => {},
-                        }
+                            }
+                        },
+                        serde_json::Value::String(ref mut value) => {
+                            window.record(draw, asset, &v.info, value);
+                        },
+                        _ => {},
                     }
-                });
+                }
+            });
 
-                self.separator(draw, Vector2::new(Self::EDIT_SHAPE - 24.0, 2.0));
+            self.separator(draw, Vector2::new(Self::EDIT_SHAPE - 24.0, 2.0));
 
-                break;
+            // the entity was edited, record the world as it was before.
+            if *entity != before {
+                let mut world = editor.world.clone();
+                world.entity[i] = before;
+                editor.history.push_group(&world, HistoryGroup::Entity(i));
             }
         }
 
         self.record(draw, asset, "Search Ent.", &mut editor.search_ent);
 
+        let mut primitive: Option<PrimitiveKind> = None;
+        let mut problem: Option<(Problem, Option<ProblemFix>)> = None;
+        let mut problem_all = false;
+
         self.scroll(asset, draw, "##Entity Scroll", Rectangle::new(self.point.x, self.point.y, Self::EDIT_SHAPE - 24.0, draw_shape.y - self.point.y - 16.0), |window, draw, scroll| {
+            window.drop(&editor.asset, draw, "Primitive", |window, draw| {
+                window.record_number(draw, asset, "Side##Primitive",      &mut editor.primitive.side);
+                window.record_number(draw, asset, "Radius##Primitive",    &mut editor.primitive.radius);
+                window.record_number(draw, asset, "Height##Primitive",    &mut editor.primitive.height);
+                window.record_number(draw, asset, "Step##Primitive",      &mut editor.primitive.step);
+                window.record_number(draw, asset, "Angle##Primitive",     &mut editor.primitive.angle);
+                window.record_number(draw, asset, "Thickness##Primitive", &mut editor.primitive.thickness);
+
+                for kind in PrimitiveKind::LIST {
+                    if window.button_shape(draw, asset, kind.name(), Some(Vector2::new(Self::EDIT_SHAPE - 24.0, 32.0)), None, true).0.click {
+                        primitive = Some(kind);
+                    }
+                }
+            });
+
+            window.drop(&editor.asset, draw, "Check", |window, draw| {
+                let list = editor.problem.get(&editor.world, &editor.history, editor.user.micro_brush);
+
+                if list.is_empty() {
+                    window.text(draw, asset, "No problem found.", Self::COLOR_TEXT_WHITE);
+                } else if window.button_shape(draw, asset, "Fix All##Check", Some(Vector2::new(Self::EDIT_SHAPE - 24.0, 32.0)), None, true).0.click {
+                    problem_all = true;
+                }
+
+                // click on a problem to select it, or on a fix under it to apply it.
+                for (i, p) in list.iter().take(Editor::CHECK_SHOW).enumerate() {
+                    if window.button_shape(draw, asset, &format!("{}##Check{i}", p.name()), Some(Vector2::new(Self::EDIT_SHAPE - 24.0, 32.0)), None, true).0.click {
+                        problem = Some((*p, None));
+                    }
+
+                    for fix in p.kind.fix_list() {
+                        if window.button_shape(draw, asset, &format!("  {}##Check{i}", fix.name()), Some(Vector2::new(Self::EDIT_SHAPE - 24.0, 32.0)), None, true).0.click {
+                            problem = Some((*p, Some(*fix)));
+                        }
+                    }
+                }
+
+                if list.len() > Editor::CHECK_SHOW {
+                    window.text(draw, asset, &format!("{} more problem(s) not shown.", list.len() - Editor::CHECK_SHOW), Self::COLOR_TEXT_WHITE);
+                }
+            });
+
             for entity in &editor.script.meta.entity {
                 if !entity.name.starts_with(&editor.search_ent) {
                     continue;
                 }
 
                 if window.button_shape(draw, asset, &entity.name, Some(Vector2::new(Self::EDIT_SHAPE - 24.0, 32.0)), None, true).0.click {
+                    editor.history.push(&editor.world);
                     editor.world.entity.push(Entity::new_from_lua(entity.clone()));
                 }
             }
         });
+
+        if let Some(kind) = primitive {
+            editor.primitive(kind);
+        }
+
+        match problem {
+            Some((p, Some(fix))) => editor.check_fix(p, fix),
+            Some((p, None)) => p.select(&mut editor.world),
+            None => {}
+        }
+
+        if problem_all {
+            editor.check_fix_all();
+        }
     }
 
     fn separator(&mut self, draw: &mut RaylibDrawHandle, shape: Vector2) {
@@ -429,6 +967,17 @@ impl Window {
 
         let pin = Vector2::new(draw_shape.x - Self::EDIT_SHAPE, Self::TOOL_SHAPE + 64.0);
         let port = Rectangle::new(pin.x, pin.y, Self::EDIT_SHAPE, 152.0);
+        let middle = Vector2::new(port.x + port.width * 0.5, port.y + port.height * 0.5);
+
+        // an edit of the selected faces ends once the value being dragged or clicked is let go of.
+        if editor.user.interact.get_release(draw) && matches!(editor.history.group(), Some(HistoryGroup::Face(_))) {
+            editor.history.end_group();
+        }
+
+        // every change is made to a copy of the first selected face, then carried over to every selected face.
+        let mut face = editor.world.face_focus().first().map(|f| (**f).clone());
+        let before = face.clone();
+        let mut reset = false;
 
         {
             let mut scissor = draw.begin_scissor_mode(
@@ -438,104 +987,141 @@ impl Window {
                 port.height as i32,
             );
 
-            scissor.draw_rectangle(
-                port.x as i32,
-                port.y as i32,
-                port.width as i32,
-                port.height as i32,
-                Color::RED,
-            );
-
-            let shape = Vector2::new(
-                (Self::EDIT_SHAPE / 128.0) * editor.asset.inner.default.width as f32,
-                (Self::EDIT_SHAPE / 128.0) * editor.asset.inner.default.height as f32,
-            );
+            scissor.draw_rectangle_rec(port, Self::COLOR_TEXT_BLACK);
 
-            scissor.draw_texture_pro(
-                &editor.asset.inner.default,
-                Rectangle::new(0.0, 0.0, shape.x * 1.0, shape.y * 1.0),
-                Rectangle::new(
-                    pin.x,
-                    pin.y,
-                    128.0 * (Self::EDIT_SHAPE / 128.0),
-                    128.0 * (Self::EDIT_SHAPE / 128.0),
-                ),
-                Vector2::new(0.0, 0.0),
-                0.0,
-                Color::WHITE,
-            );
+            // draw the face as a square, with the texture laid over it as it would be on a fit face.
+            if let Some(face) = &face {
+                let texture = face
+                    .texture
+                    .as_ref()
+                    .and_then(|texture| editor.asset.outer.texture.get(texture))
+                    .unwrap_or(&editor.asset.inner.default);
+                let shape = Vector2::new(texture.width as f32, texture.height as f32);
 
-            scissor.draw_rectangle_lines_ex(
-                Rectangle::new(pin.x, pin.y, 128.0, 128.0),
-                2.0,
-                Color::RED,
-            );
+                scissor.draw_texture_pro(
+                    texture,
+                    Rectangle::new(
+                        face.scale.x * face.shift.x * shape.x,
+                        face.scale.y * face.shift.y * shape.y,
+                        face.scale.x * shape.x,
+                        face.scale.y * shape.y,
+                    ),
+                    Rectangle::new(middle.x, middle.y, Self::PREVIEW_SHAPE, Self::PREVIEW_SHAPE),
+                    Vector2::new(Self::PREVIEW_SHAPE * 0.5, Self::PREVIEW_SHAPE * 0.5),
+                    -face.angle,
+                    face.color,
+                );
 
-            scissor.draw_circle_v(
-                pin + Vector2::new((128.0 * 0.0) - 4.0, (128.0 * 0.0) - 4.0),
-                8.0,
-                Color::RED,
-            );
+                scissor.draw_rectangle_lines_ex(
+                    Rectangle::new(
+                        middle.x - Self::PREVIEW_SHAPE * 0.5,
+                        middle.y - Self::PREVIEW_SHAPE * 0.5,
+                        Self::PREVIEW_SHAPE,
+                        Self::PREVIEW_SHAPE,
+                    ),
+                    2.0,
+                    Self::COLOR_PRIMARY_MAIN,
+                );
+            }
+        }
 
+        if let Some(face) = &mut face {
             if port.check_collision_point_rec(draw_mouse) {
-                let mut mouse = scissor.get_mouse_delta();
-                let wheel = scissor.get_mouse_wheel_move();
+                let mut mouse = draw.get_mouse_delta();
 
-                if scissor.is_key_down(KeyboardKey::KEY_X) {
+                if draw.is_key_down(KeyboardKey::KEY_X) {
                     mouse.y = 0.0;
                 }
-                if scissor.is_key_down(KeyboardKey::KEY_Y) {
+                if draw.is_key_down(KeyboardKey::KEY_Y) {
                     mouse.x = 0.0;
                 }
 
-                if scissor.is_mouse_button_down(MouseButton::MOUSE_BUTTON_LEFT) {
-                    self.tool_tip(
-                        &mut scissor,
-                        asset, 
-                        pin,
-                        &format!("0, 0"),
-                        None,
-                    );
-                } else if scissor.is_mouse_button_down(MouseButton::MOUSE_BUTTON_RIGHT) {
-                    self.tool_tip(
-                        &mut scissor,
-                        asset, 
-                        pin,
-                        &format!("0, 0"),
-                        None,
-                    );
+                let delta = mouse / Self::PREVIEW_SHAPE;
 
-                } else if scissor.is_mouse_button_down(MouseButton::MOUSE_BUTTON_MIDDLE) {
+                if draw.is_mouse_button_down(MouseButton::MOUSE_BUTTON_LEFT) {
+                    face.shift -= delta;
+                } else if draw.is_mouse_button_down(MouseButton::MOUSE_BUTTON_RIGHT) {
+                    face.scale += delta;
+                } else if draw.is_mouse_button_down(MouseButton::MOUSE_BUTTON_MIDDLE) {
+                    face.angle += mouse.x;
                 }
 
-                if scissor.is_key_pressed(KeyboardKey::KEY_SPACE) {
+                if draw.is_key_pressed(KeyboardKey::KEY_SPACE) {
+                    reset = true;
                 }
-            }
-        }
 
-        if port.check_collision_point_rec(draw_mouse) {
-            let mut y = 3.0;
-            let point = Vector2::new(20.0, draw_shape.y - (256.0 + 10.0));
+                let mut y = 3.0;
+                let point = Vector2::new(20.0, draw_shape.y - (256.0 + 10.0));
 
-            self.tool_tip(draw, asset, point + Vector2::new(0.0, 36.0 * y), "Shift", Some(&Input::new(None, Key::Mouse(MouseButton::MOUSE_BUTTON_LEFT)))); y += 1.0;
-            self.tool_tip(draw, asset, point + Vector2::new(0.0, 36.0 * y), "Scale", Some(&Input::new(None, Key::Mouse(MouseButton::MOUSE_BUTTON_LEFT)))); y += 1.0;
-            self.tool_tip(draw, asset, point + Vector2::new(0.0, 36.0 * y), "Angle", Some(&Input::new(None, Key::Mouse(MouseButton::MOUSE_BUTTON_LEFT)))); y += 1.0;
-            self.tool_tip(draw, asset, point + Vector2::new(0.0, 36.0 * y), "Reset", Some(&Input::new(None, Key::Mouse(MouseButton::MOUSE_BUTTON_LEFT))));
+                self.tool_tip(draw, asset, point + Vector2::new(0.0, 36.0 * y), "Shift", Some(&Input::new(None, Key::Mouse(MouseButton::MOUSE_BUTTON_LEFT))));   y += 1.0;
+                self.tool_tip(draw, asset, point + Vector2::new(0.0, 36.0 * y), "Scale", Some(&Input::new(None, Key::Mouse(MouseButton::MOUSE_BUTTON_RIGHT))));  y += 1.0;
+                self.tool_tip(draw, asset, point + Vector2::new(0.0, 36.0 * y), "Angle", Some(&Input::new(None, Key::Mouse(MouseButton::MOUSE_BUTTON_MIDDLE)))); y += 1.0;
+                self.tool_tip(draw, asset, point + Vector2::new(0.0, 36.0 * y), "Reset", Some(&Input::new(None, Key::Keyboard(KeyboardKey::KEY_SPACE))));
+            }
+        } else {
+            let text = "No face selected.";
+            let measure = self.font_measure(asset, text);
+
+            self.font(draw, asset, text, middle - measure * 0.5, Self::COLOR_TEXT_WHITE);
         }
 
         let p = Vector2::new(pin.x + 12.0, pin.y + 160.0);
 
         self.point(p);
         self.separator(draw, Vector2::new(Self::EDIT_SHAPE - 24.0, 2.0));
+
+        // cycle the projection of every selected face, going by the first one.
+        if let Some(projection) = face.as_ref().map(|f| f.projection) {
+            if self.button_shape(draw, asset, &format!("Projection: {}##Projection", projection.name()), Some(Vector2::new(Self::EDIT_SHAPE - 24.0, 32.0)), None, true).0.click {
+                editor.history.push(&editor.world);
+
+                for face in editor.world.face_focus() {
+                    face.projection = projection.next();
+                }
+            }
+        }
+
         self.record(draw, asset, "Search Tex.", &mut editor.search_tex);
 
         let mut tool: Option<(Vector2, String)> = None;
+        let mut align: Option<TextureAlign> = None;
+        let mut pick: Option<String> = None;
+
+        self.scroll(asset, draw, "##Texture", Rectangle::new(self.point.x, self.point.y, Self::EDIT_SHAPE - 24.0, draw_shape.y - self.point.y - 16.0), |window, draw, _| {
+            if let Some(face) = &mut face {
+                window.drop(&editor.asset, draw, "Face", |window, draw| {
+                    let mut color = [face.color.r as f32, face.color.g as f32, face.color.b as f32, face.color.a as f32];
+
+                    window.record_number(draw, asset, "Shift X##Face", &mut face.shift.x);
+                    window.record_number(draw, asset, "Shift Y##Face", &mut face.shift.y);
+                    window.record_number(draw, asset, "Scale X##Face", &mut face.scale.x);
+                    window.record_number(draw, asset, "Scale Y##Face", &mut face.scale.y);
+                    window.record_number(draw, asset, "Angle##Face",   &mut face.angle);
+                    window.record_number(draw, asset, "Red##Face",     &mut color[0]);
+                    window.record_number(draw, asset, "Green##Face",   &mut color[1]);
+                    window.record_number(draw, asset, "Blue##Face",    &mut color[2]);
+                    window.record_number(draw, asset, "Alpha##Face",   &mut color[3]);
+
+                    face.color = Color::new(
+                        color[0].clamp(0.0, 255.0) as u8,
+                        color[1].clamp(0.0, 255.0) as u8,
+                        color[2].clamp(0.0, 255.0) as u8,
+                        color[3].clamp(0.0, 255.0) as u8,
+                    );
 
-        self.scroll(asset, draw, "##Texture", Rectangle::new(self.point.x, self.point.y, Self::EDIT_SHAPE - 24.0, draw_shape.y - self.point.y - 16.0), |window, draw, scroll| {
+                    for kind in TextureAlign::LIST {
+                        if window.button_shape(draw, asset, &format!("{}##Face", kind.name()), Some(Vector2::new(Self::EDIT_SHAPE - 24.0, 32.0)), None, true).0.click {
+                            align = Some(kind);
+                        }
+                    }
+                });
+            }
+
+            let start = window.point;
             let mut j = 0;
 
-            for (name, texture) in &editor.asset.outer.texture {
-                let name = name.replace(&editor.game.path, "");
+            for (path, texture) in &editor.asset.outer.texture {
+                let name = path.replace(&editor.game.path, "");
 
                 if !name.starts_with(&editor.search_tex) {
                     continue;
@@ -544,7 +1130,7 @@ impl Window {
                 let s = (Self::EDIT_SHAPE / 72.0).floor();
                 let x = (j as f32 % s).floor();
                 let y = (j as f32 / s).floor();
-                let p = scroll + Vector2::new(x * 72.0, y * 72.0);
+                let p = start + Vector2::new(x * 72.0, y * 72.0);
 
                 window.point(p);
                 let state =
@@ -555,13 +1141,7 @@ impl Window {
                 }
 
                 if state.0.click {
-                    for brush in &mut editor.world.brush {
-                        if brush.focus {
-                            for f in &mut brush.face {
-                                f.texture = Some(name.clone());
-                            }
-                        }
-                    }
+                    pick = Some(path.clone());
                 }
 
                 j += 1;
@@ -571,6 +1151,45 @@ impl Window {
         if let Some(tool) = tool {
             self.tool_tip(draw, asset, tool.0, &tool.1, None);
         }
+
+        // carry the change over to every selected face, by the difference, so that each keeps its own shift, scale and angle.
+        if let (Some(face), Some(before)) = (face, before) {
+            if reset || face.shift != before.shift || face.scale != before.scale || face.angle != before.angle || face.color != before.color {
+                if reset {
+                    editor.history.push(&editor.world);
+                } else {
+                    editor.history.push_group(&editor.world, HistoryGroup::Face(editor.world.selection.clone()));
+                }
+
+                for f in editor.world.face_focus() {
+                    if reset {
+                        f.shift = Vector2::zero();
+                        f.scale = Vector2::one();
+                        f.angle = 0.0;
+                    } else {
+                        f.shift += face.shift - before.shift;
+                        f.scale += face.scale - before.scale;
+                        f.angle += face.angle - before.angle;
+
+                        if face.color != before.color {
+                            f.color = face.color;
+                        }
+                    }
+                }
+            }
+        }
+
+        if let Some(align) = align {
+            editor.face_align(align);
+        }
+
+        if let Some(path) = pick {
+            editor.history.push(&editor.world);
+
+            for face in editor.world.face_focus() {
+                face.texture = Some(path.clone());
+            }
+        }
     }
 
     fn measure_input(